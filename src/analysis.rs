@@ -0,0 +1,394 @@
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+//! Computations over data returned by the API.
+
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::Result;
+use futures::{future, Stream, TryStreamExt};
+
+use crate::{
+    profile_games,
+    types::{
+        civilization::Civilization,
+        games::{Game, Player},
+        leaderboards::Leaderboard,
+        maps::Map,
+        profile::ProfileId,
+    },
+};
+
+/// A player's wins and losses over a set of games.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Record {
+    /// Number of games won.
+    pub wins: u32,
+    /// Number of games lost.
+    pub losses: u32,
+}
+
+impl Record {
+    /// Number of decided games.
+    pub fn games(&self) -> u32 {
+        self.wins + self.losses
+    }
+
+    /// Win rate as a percentage out of 100, or `None` if no games were decided.
+    pub fn win_rate(&self) -> Option<f64> {
+        match self.games() {
+            0 => None,
+            games => Some(100.0 * self.wins as f64 / games as f64),
+        }
+    }
+}
+
+/// Tallies a player's wins and losses over a stream of games.
+///
+/// Games where the player did not play or which have no decided result are skipped. Pairs
+/// naturally with [`crate::head_to_head`] to get the record of one player against another.
+pub async fn record(
+    games: impl Stream<Item = Result<Game>>,
+    profile_id: impl Into<ProfileId>,
+) -> Result<Record> {
+    let profile_id = profile_id.into();
+    games
+        .try_fold(Record::default(), |mut record, game| async move {
+            match game.player(profile_id).and_then(Player::won) {
+                Some(true) => record.wins += 1,
+                Some(false) => record.losses += 1,
+                None => {}
+            }
+            Ok(record)
+        })
+        .await
+}
+
+/// Tallies a player's wins and losses with each civilization over a stream of games.
+///
+/// Like [`record`], but split by the civilization the player played, on whichever team they
+/// were. Games where the player's civilization is unknown are skipped as well. Stops at the
+/// first error in the stream.
+pub async fn record_by_civ(
+    games: impl Stream<Item = Result<Game>>,
+    profile_id: impl Into<ProfileId>,
+) -> Result<HashMap<Civilization, Record>> {
+    let profile_id = profile_id.into();
+    games
+        .try_fold(HashMap::new(), |mut records, game| async move {
+            let Some(player) = game.player(profile_id) else {
+                return Ok(records);
+            };
+            let (Some(civ), Some(won)) = (player.civilization, player.won()) else {
+                return Ok(records);
+            };
+            let record: &mut Record = records.entry(civ).or_default();
+            match won {
+                true => record.wins += 1,
+                false => record.losses += 1,
+            }
+            Ok(records)
+        })
+        .await
+}
+
+/// Tallies a player's wins and losses on each map over a stream of games.
+///
+/// Like [`record`], but split by [`Game::map`]. Games without a map are skipped as well.
+/// Stops at the first error in the stream.
+pub async fn record_by_map(
+    games: impl Stream<Item = Result<Game>>,
+    profile_id: impl Into<ProfileId>,
+) -> Result<HashMap<Map, Record>> {
+    let profile_id = profile_id.into();
+    games
+        .try_fold(HashMap::new(), |mut records, game| async move {
+            let won = game.player(profile_id).and_then(Player::won);
+            let (Some(map), Some(won)) = (game.map, won) else {
+                return Ok(records);
+            };
+            let record: &mut Record = records.entry(map).or_default();
+            match won {
+                true => record.wins += 1,
+                false => record.losses += 1,
+            }
+            Ok(records)
+        })
+        .await
+}
+
+/// Pairs each game in a stream with a player's win rate over a trailing window of games.
+///
+/// `games` should be in chronological order, so streams returned by the API (most recent
+/// first) need to be reversed. The win rate is a percentage out of 100 over the player's last
+/// `window` decided games, up to and including the current one. Games where the player did
+/// not play or which have no decided result are skipped. A `window` of 0 is treated as 1.
+pub fn rolling_win_rate(
+    games: impl Stream<Item = Result<Game>>,
+    profile_id: impl Into<ProfileId>,
+    window: usize,
+) -> impl Stream<Item = Result<(Game, f64)>> {
+    let profile_id = profile_id.into();
+    let window = window.max(1);
+    let mut won = VecDeque::with_capacity(window);
+    games.try_filter_map(move |game| {
+        let Some(result) = game.player(profile_id).and_then(Player::won) else {
+            return future::ready(Ok(None));
+        };
+        if won.len() == window {
+            won.pop_front();
+        }
+        won.push_back(result);
+        let wins = won.iter().filter(|w| **w).count();
+        let win_rate = 100.0 * wins as f64 / won.len() as f64;
+        future::ready(Ok(Some((game, win_rate))))
+    })
+}
+
+/// Returns the slope of a player's rating over their last `window` games on a leaderboard,
+/// in rating points per game.
+///
+/// A positive slope means the player is climbing. Returns `None` if fewer than two of the
+/// games have a known rating for the player.
+///
+/// # Params
+/// - `profile_id` is the aoe4world ID of the player.
+/// - `leaderboard` is the leaderboard whose games should be considered.
+/// - `window` is the number of most recent games to fit.
+pub async fn rating_trend(
+    profile_id: impl Into<ProfileId>,
+    leaderboard: Leaderboard,
+    window: usize,
+) -> Result<Option<f64>> {
+    if window == 0 {
+        return Ok(None);
+    }
+    let profile_id = profile_id.into();
+    let games: Vec<Game> = profile_games(profile_id)
+        .with_leaderboard(Some(vec![leaderboard]))
+        .get(window)
+        .await?
+        .try_collect()
+        .await?;
+    Ok(games_rating_trend(&games, profile_id))
+}
+
+/// Returns the slope of a player's rating over `games`, in rating points per game.
+///
+/// `games` are expected in the order returned by the API (most recent first). The rating
+/// after each game is its `rating` plus `rating_diff`. Games where the player's rating is
+/// unknown are skipped. Returns `None` if fewer than two ratings are known.
+pub fn games_rating_trend(games: &[Game], profile_id: ProfileId) -> Option<f64> {
+    let ratings: Vec<f64> = games
+        .iter()
+        .rev()
+        .filter_map(|game| {
+            let player = game.player(profile_id)?;
+            let rating = player.rating? as i64 + player.rating_diff.unwrap_or_default();
+            Some(rating as f64)
+        })
+        .collect();
+    slope(&ratings)
+}
+
+/// Least squares slope of `ys` over their indices.
+fn slope(ys: &[f64]) -> Option<f64> {
+    if ys.len() < 2 {
+        return None;
+    }
+    let n = ys.len() as f64;
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = ys.iter().sum::<f64>() / n;
+    let (cov, var) = ys
+        .iter()
+        .enumerate()
+        .fold((0.0, 0.0), |(cov, var), (x, y)| {
+            let dx = x as f64 - mean_x;
+            (cov + dx * (y - mean_y), var + dx * dx)
+        });
+    Some(cov / var)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pagination::Paginated, types::games::ProfileGames};
+
+    use super::*;
+
+    const NEPTUNE_ID: u64 = 4635035;
+
+    fn games(json_str: &str) -> Vec<Game> {
+        let games: ProfileGames = serde_json::from_str(json_str).expect("should deserialize");
+        games.data()
+    }
+
+    #[test]
+    fn test_slope() {
+        assert_eq!(None, slope(&[]));
+        assert_eq!(None, slope(&[1000.0]));
+        assert_eq!(Some(0.0), slope(&[1000.0, 1000.0, 1000.0]));
+        assert_eq!(Some(-5.0), slope(&[1010.0, 1005.0, 1000.0]));
+    }
+
+    #[test]
+    fn test_games_rating_trend_up() {
+        let games = games(include_str!("../testdata/games/rating_trend_up.json"));
+        let trend = games_rating_trend(&games, NEPTUNE_ID.into());
+        assert_eq!(Some(20.0), trend);
+    }
+
+    #[test]
+    fn test_games_rating_trend_insufficient_data() {
+        let games = games(include_str!("../testdata/games/rating_trend_up.json"));
+        assert_eq!(None, games_rating_trend(&games[..1], NEPTUNE_ID.into()));
+        assert_eq!(None, games_rating_trend(&games, 1.into()));
+    }
+
+    #[tokio::test]
+    async fn test_record() {
+        let games = games(include_str!("../testdata/games/neptune.json"));
+        let record = record(futures::stream::iter(games.into_iter().map(Ok)), NEPTUNE_ID)
+            .await
+            .expect("should succeed");
+        assert_eq!(
+            Record {
+                wins: 41,
+                losses: 9
+            },
+            record
+        );
+        assert_eq!(50, record.games());
+        assert_eq!(Some(82.0), record.win_rate());
+        assert_eq!(None, Record::default().win_rate());
+    }
+
+    #[tokio::test]
+    async fn test_record_by_civ() {
+        let games = games(include_str!("../testdata/games/neptune.json"));
+        let records = record_by_civ(futures::stream::iter(games.into_iter().map(Ok)), NEPTUNE_ID)
+            .await
+            .expect("should succeed");
+        let record = |wins, losses| Record { wins, losses };
+        let want = HashMap::from([
+            (Civilization::Malians, record(13, 4)),
+            (Civilization::Chinese, record(16, 4)),
+            (Civilization::French, record(4, 1)),
+            (Civilization::HolyRomanEmpire, record(3, 0)),
+            (Civilization::Mongols, record(3, 0)),
+            (Civilization::AbbasidDynasty, record(1, 0)),
+            (Civilization::Rus, record(1, 0)),
+        ]);
+        assert_eq!(want, records);
+        assert_eq!(Some(80.0), records[&Civilization::Chinese].win_rate());
+
+        // The per-civ records add up to the overall record.
+        let total = records
+            .values()
+            .fold(Record::default(), |total, record| Record {
+                wins: total.wins + record.wins,
+                losses: total.losses + record.losses,
+            });
+        assert_eq!(record(41, 9), total);
+
+        let records = record_by_civ(futures::stream::iter(Vec::new()), NEPTUNE_ID)
+            .await
+            .expect("should succeed");
+        assert!(records.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_record_by_civ_error() {
+        let games = games(include_str!("../testdata/games/neptune.json"));
+        let stream = futures::stream::iter(
+            [Ok(games[0].clone()), Err(anyhow::anyhow!("page failed"))]
+                .into_iter()
+                .chain(games.into_iter().skip(1).map(Ok)),
+        );
+        let err = record_by_civ(stream, NEPTUNE_ID)
+            .await
+            .expect_err("should fail");
+        assert_eq!("page failed", err.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_record_by_map() {
+        let mut games = games(include_str!("../testdata/games/neptune.json"));
+        // A loss on Forest Ponds, which is skipped once its map is unknown.
+        games[0].map = None;
+        let records = record_by_map(futures::stream::iter(games.into_iter().map(Ok)), NEPTUNE_ID)
+            .await
+            .expect("should succeed");
+        let record = |wins, losses| Record { wins, losses };
+        let want = HashMap::from([
+            (Map::DryArabia, record(7, 2)),
+            (Map::Altai, record(6, 1)),
+            (Map::ForestPonds, record(5, 1)),
+            (Map::ThePit, record(5, 0)),
+            (Map::Lipany, record(4, 3)),
+            (Map::Mediterranean, record(4, 0)),
+            (Map::Wetlands, record(2, 1)),
+            (Map::Hideout, record(2, 0)),
+            (Map::FrenchPass, record(2, 0)),
+            (Map::Prairie, record(2, 0)),
+            (Map::HillAndDale, record(1, 0)),
+            (Map::AncientSpires, record(1, 0)),
+        ]);
+        assert_eq!(want, records);
+        assert_eq!(Some(100.0), records[&Map::ThePit].win_rate());
+    }
+
+    #[tokio::test]
+    async fn test_rolling_win_rate() {
+        let mut games = games(include_str!("../testdata/games/neptune.json"));
+        games.reverse();
+        let first_id = games[0].game_id;
+
+        let rates: Vec<(Game, f64)> = rolling_win_rate(
+            futures::stream::iter(games.into_iter().map(Ok)),
+            NEPTUNE_ID,
+            3,
+        )
+        .try_collect()
+        .await
+        .expect("should succeed");
+
+        assert_eq!(50, rates.len());
+        assert_eq!(first_id, rates[0].0.game_id);
+        let want = [
+            100.0,
+            100.0,
+            100.0,
+            100.0,
+            100.0,
+            100.0,
+            200.0 / 3.0,
+            200.0 / 3.0,
+            100.0 / 3.0,
+            200.0 / 3.0,
+        ];
+        for (i, (want, (_, got))) in want.iter().zip(&rates).enumerate() {
+            assert!(
+                (want - got).abs() < 1e-9,
+                "game {i}: want {want}, got {got}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rolling_win_rate_skips_missing_player() {
+        let games = games(include_str!("../testdata/games/neptune.json"));
+        let rates: Vec<(Game, f64)> =
+            rolling_win_rate(futures::stream::iter(games.into_iter().map(Ok)), 1, 3)
+                .try_collect()
+                .await
+                .expect("should succeed");
+        assert!(rates.is_empty());
+    }
+
+    #[cfg_attr(not(feature = "test-api"), ignore)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rating_trend_api_smoke() {
+        rating_trend(NEPTUNE_ID, Leaderboard::RmSolo, 10)
+            .await
+            .expect("API call should succeed");
+    }
+}
@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+//! Blocking versions of the queries, for callers without an async runtime.
+//!
+//! Each `*_blocking` method runs its async counterpart to completion on a runtime shared by
+//! all blocking queries. Streams are collected into a [`Vec`], failing on the first error.
+//!
+//! Queries not given a client use one shared by blocking queries only, with its own
+//! connection pool. A [`crate::Client`] passed to a blocking query should not also be used
+//! from async code, as its pooled connections and requests in flight are tied to the
+//! runtime they were started on.
+//!
+//! These methods panic if called from within an async runtime. Use the async methods there
+//! instead.
+
+use std::{future::Future, sync::OnceLock};
+
+use anyhow::Result;
+use futures::TryStreamExt;
+use tokio::runtime::{Builder, Runtime};
+
+use crate::{
+    client::BLOCKING,
+    query::{
+        GameQuery, GlobalGamesQuery, LeaderboardQuery, ProfileGamesQuery, ProfileQuery,
+        ProfilesQuery, SearchQuery,
+    },
+    types::{games::Game, leaderboards::LeaderboardEntry, profile::Profile},
+};
+
+/// Runs `future` to completion on the runtime shared by all blocking queries.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME
+        .get_or_init(|| {
+            Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("runtime should build")
+        })
+        .block_on(BLOCKING.scope((), future))
+}
+
+impl ProfileQuery {
+    /// Blocking version of [`Self::get`].
+    pub fn get_blocking(self) -> Result<Profile> {
+        block_on(self.get())
+    }
+}
+
+impl ProfilesQuery {
+    /// Blocking version of [`Self::get`]. Profiles are in the same order as the requested
+    /// profile IDs.
+    pub fn get_blocking(self) -> Result<Vec<Profile>> {
+        block_on(async { self.get().await?.try_collect().await })
+    }
+}
+
+impl ProfileGamesQuery {
+    /// Blocking version of [`Self::get`].
+    pub fn get_blocking(self, limit: usize) -> Result<Vec<Game>> {
+        block_on(async { self.get(limit).await?.try_collect().await })
+    }
+
+    /// Blocking version of [`Self::get_all`].
+    pub fn get_all_blocking(self) -> Result<Vec<Game>> {
+        block_on(async { self.get_all().await?.try_collect().await })
+    }
+
+    /// Blocking version of [`Self::latest`].
+    pub fn latest_blocking(self) -> Result<Option<Game>> {
+        block_on(self.latest())
+    }
+}
+
+impl GameQuery {
+    /// Blocking version of [`Self::get`].
+    pub fn get_blocking(self) -> Result<Game> {
+        block_on(self.get())
+    }
+}
+
+impl GlobalGamesQuery {
+    /// Blocking version of [`Self::get`].
+    pub fn get_blocking(self, limit: usize) -> Result<Vec<Game>> {
+        block_on(async { self.get(limit).await?.try_collect().await })
+    }
+
+    /// Blocking version of [`Self::get_all`].
+    pub fn get_all_blocking(self) -> Result<Vec<Game>> {
+        block_on(async { self.get_all().await?.try_collect().await })
+    }
+}
+
+impl SearchQuery {
+    /// Blocking version of [`Self::get`].
+    pub fn get_blocking(self, limit: usize) -> Result<Vec<Profile>> {
+        block_on(async { self.get(limit).await?.try_collect().await })
+    }
+}
+
+impl LeaderboardQuery {
+    /// Blocking version of [`Self::get`].
+    pub fn get_blocking(self, limit: usize) -> Result<Vec<LeaderboardEntry>> {
+        block_on(async { self.get(limit).await?.try_collect().await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use reqwest::StatusCode;
+
+    use crate::{
+        client::Response, global_games, pagination::Paginated, profile, profile_games,
+        testutils::MockTransport, types::games::GlobalGames, Client,
+    };
+
+    #[test]
+    fn test_get_blocking() {
+        let fixture = include_str!("../testdata/games/global.json");
+        let want: Vec<u32> = serde_json::from_str::<GlobalGames>(fixture)
+            .unwrap()
+            .data()
+            .iter()
+            .map(|game| game.game_id)
+            .collect();
+
+        let transport = Arc::new(
+            MockTransport::pages(fixture, "games", 120, true).with_delay(|_| Duration::ZERO),
+        );
+        let client = Client::with_transport(Client::builder(), transport.clone());
+        let games = global_games()
+            .with_client(Some(client.clone()))
+            .get_blocking(60)
+            .expect("query should succeed");
+        let got: Vec<u32> = games.iter().map(|game| game.game_id).collect();
+        assert_eq!(
+            want.iter().cycle().take(60).copied().collect::<Vec<_>>(),
+            got
+        );
+
+        // The shared runtime is reused across calls.
+        let game = profile_games(3176)
+            .with_client(Some(client))
+            .latest_blocking()
+            .expect("query should succeed");
+        assert_eq!(Some(want[0]), game.map(|game| game.game_id));
+        assert_eq!(3, transport.requests().len());
+    }
+
+    #[test]
+    fn test_get_blocking_error() {
+        let transport = Arc::new(MockTransport::new(|_| Response {
+            status: StatusCode::NOT_FOUND,
+            body: include_str!("../testdata/profile/not_found.json").into(),
+        }));
+        let client = Client::with_transport(Client::builder(), transport);
+        assert!(profile(3176)
+            .with_client(Some(client))
+            .get_blocking()
+            .is_err());
+    }
+}
@@ -0,0 +1,543 @@
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+//! Disk-backed cache of API responses. Enabled by the `disk-cache` feature.
+
+// Clippy complains about needless update in derived setters.
+#![allow(clippy::needless_update)]
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use derive_setters::Setters;
+use reqwest::StatusCode;
+use serde_json::Value;
+use tokio::fs;
+use url::Url;
+
+use crate::client::{Endpoint, Response, Transport};
+
+/// Default maximum total size of a [`Cache`] in bytes.
+const DEFAULT_MAX_SIZE: u64 = 1 << 30;
+
+/// Earliest season not known to be over when this was written. Seasons only move forward,
+/// so treating it as the current season never caches a live leaderboard.
+const DEFAULT_CURRENT_SEASON: u32 = 8;
+
+/// Extension of the files holding cached responses.
+const EXTENSION: &str = "response";
+
+/// Decides which requests may be served from a [`Cache`].
+///
+/// Regardless of the policy, only successful responses are cached, and responses containing
+/// ongoing games or games waiting for their results are never cached.
+#[derive(Debug, Default, Clone, Copy)]
+#[non_exhaustive]
+pub enum CachePolicy {
+    /// Cache responses for data which can no longer change: single finished games, and
+    /// leaderboards of seasons before the current one (see [`Cache::with_current_season`]).
+    /// Live leaderboards and pages of games are not cached.
+    #[default]
+    Immutable,
+    /// Like [`CachePolicy::Immutable`], but also cache pages of games which have all
+    /// finished.
+    ///
+    /// New games push older ones onto later pages, but cached pages are served as they
+    /// were, so queries such as `latest` keep returning the same games. This suits
+    /// backfills of past games; clear the cache to see new ones.
+    FinishedGamePages,
+    /// Cache every response. Suitable when the data is known not to change over the
+    /// lifetime of the cache, e.g. for a one-off batch job.
+    All,
+    /// Cache responses for URLs matching the predicate.
+    Custom(fn(&Url) -> bool),
+}
+
+impl CachePolicy {
+    /// Can the response to a request for `url` be cached, while `current_season` is being
+    /// played?
+    fn is_cacheable(&self, url: &Url, current_season: u32) -> bool {
+        match self {
+            CachePolicy::Immutable => {
+                let past_season = url.query_pairs().any(|(k, v)| {
+                    k == "season" && v.parse::<u32>().is_ok_and(|season| season < current_season)
+                });
+                Endpoint::Game.matches(url) || Endpoint::Leaderboard.matches(url) && past_season
+            }
+            CachePolicy::FinishedGamePages => {
+                [Endpoint::Games, Endpoint::ProfileGames]
+                    .iter()
+                    .any(|endpoint| endpoint.matches(url))
+                    || CachePolicy::Immutable.is_cacheable(url, current_season)
+            }
+            CachePolicy::All => true,
+            CachePolicy::Custom(f) => f(url),
+        }
+    }
+}
+
+/// A cache of API responses stored on disk, so that they can be reused across runs.
+///
+/// Pass it to [`crate::ClientBuilder::with_cache`] to serve requests made through the
+/// [`crate::Client`] from the cache. Responses are keyed by URL. Once the cache grows past
+/// its maximum size, the least recently used responses are evicted. Failing to read or
+/// write the cache does not fail requests.
+///
+/// # Examples
+///
+/// ```rust
+/// use prelate_rs::{Cache, CachePolicy, Client};
+///
+/// let cache = Cache::new("/tmp/prelate-cache")
+///     .with_max_size(Some(100 << 20))
+///     .with_policy(Some(CachePolicy::All));
+/// let client = Client::builder().with_cache(Some(cache)).build();
+/// ```
+#[derive(Setters, Debug, Clone)]
+#[setters(prefix = "with_")]
+#[setters(into)]
+pub struct Cache {
+    /// Directory holding cached responses.
+    #[setters(skip)]
+    dir: PathBuf,
+    /// Maximum total size of cached responses in bytes. Defaults to 1 GiB.
+    max_size: Option<u64>,
+    /// Which requests may be served from the cache. Defaults to
+    /// [`CachePolicy::Immutable`].
+    policy: Option<CachePolicy>,
+    /// Season currently being played. [`CachePolicy::Immutable`] only caches leaderboards
+    /// of earlier seasons. Defaults to season 8, which leaves later finished seasons
+    /// uncached until this is set.
+    current_season: Option<u32>,
+}
+
+impl Cache {
+    /// Returns a cache storing responses in `dir`, which is created if it does not exist.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_size: None,
+            policy: None,
+            current_season: None,
+        }
+    }
+
+    /// Removes every cached response.
+    pub async fn clear(&self) -> Result<()> {
+        for (path, _) in self.entries().await? {
+            remove_file(path).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns the cached response body for `url`, if any. Unless `any_policy` is set, only
+    /// responses allowed by the policy are returned.
+    async fn get(&self, url: &Url, any_policy: bool) -> Option<String> {
+        if !any_policy && !self.is_cacheable(url) {
+            return None;
+        }
+        let path = self.path(url);
+        let contents = fs::read_to_string(&path).await.ok()?;
+        let (cached_url, body) = contents.split_once('\n')?;
+        if cached_url != url.as_str() {
+            return None;
+        }
+        // Mark the response as recently used. Failing to do so only affects eviction.
+        let _ = tokio::task::spawn_blocking(move || set_modified(&path, SystemTime::now())).await;
+        Some(body.to_string())
+    }
+
+    /// Caches the response body for `url` if the policy allows it, then evicts the least
+    /// recently used responses if the cache is too large.
+    async fn put(&self, url: &Url, body: &str) -> Result<()> {
+        if !self.is_cacheable(url) || has_unfinished_games(body) {
+            return Ok(());
+        }
+        fs::create_dir_all(&self.dir).await?;
+        // Write to a temporary file first so that readers never see a partial response.
+        let path = self.path(url);
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, format!("{url}\n{body}")).await?;
+        fs::rename(&tmp, &path).await?;
+        self.evict().await
+    }
+
+    /// Removes the least recently used responses until the cache fits in its maximum size.
+    async fn evict(&self) -> Result<()> {
+        let max_size = self.max_size.unwrap_or(DEFAULT_MAX_SIZE);
+        let mut entries = self.entries().await?;
+        let mut size: u64 = entries.iter().map(|(_, m)| m.len()).sum();
+        entries.sort_by_key(|(_, m)| m.modified().ok());
+        for (path, metadata) in entries {
+            if size <= max_size {
+                break;
+            }
+            remove_file(path).await?;
+            size -= metadata.len();
+        }
+        Ok(())
+    }
+
+    /// Returns the path and metadata of every cached response.
+    async fn entries(&self) -> Result<Vec<(PathBuf, std::fs::Metadata)>> {
+        let mut dir = match fs::read_dir(&self.dir).await {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+        let mut entries = vec![];
+        while let Some(entry) = dir.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == EXTENSION) {
+                entries.push((path, entry.metadata().await?));
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Path of the file caching the response for `url`.
+    fn path(&self, url: &Url) -> PathBuf {
+        // The hash may differ between Rust releases, which only causes cache misses. The URL
+        // is stored alongside the response to rule out collisions.
+        let mut hasher = DefaultHasher::new();
+        url.as_str().hash(&mut hasher);
+        self.dir
+            .join(format!("{:016x}", hasher.finish()))
+            .with_extension(EXTENSION)
+    }
+
+    fn is_cacheable(&self, url: &Url) -> bool {
+        let current_season = self.current_season.unwrap_or(DEFAULT_CURRENT_SEASON);
+        self.policy
+            .unwrap_or_default()
+            .is_cacheable(url, current_season)
+    }
+}
+
+/// Sets the modification time of the file at `path`. Blocks, so should be called through
+/// [`tokio::task::spawn_blocking`] from async code.
+fn set_modified(path: &Path, time: SystemTime) -> std::io::Result<()> {
+    std::fs::File::options()
+        .append(true)
+        .open(path)?
+        .set_modified(time)
+}
+
+/// Removes a file, ignoring it if it has already been removed.
+async fn remove_file(path: PathBuf) -> Result<()> {
+    match fs::remove_file(path).await {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Does the response contain games which are ongoing or waiting for their results?
+fn has_unfinished_games(body: &str) -> bool {
+    let Ok(value) = serde_json::from_str::<Value>(body) else {
+        return false;
+    };
+    let unfinished = |game: &Value| {
+        game.get("ongoing") == Some(&Value::Bool(true))
+            || game.get("just_finished") == Some(&Value::Bool(true))
+    };
+    match value.get("games").and_then(Value::as_array) {
+        Some(games) => games.iter().any(unfinished),
+        // A single game.
+        None => unfinished(&value),
+    }
+}
+
+/// [`Transport`] which serves responses from a [`Cache`], falling back to another
+/// transport. In offline mode, every cached response is served regardless of the policy.
+pub(crate) struct CachingTransport {
+    pub inner: Arc<dyn Transport>,
+    pub cache: Cache,
+    pub offline: bool,
+}
+
+#[async_trait]
+impl Transport for CachingTransport {
+    async fn get(&self, url: Url) -> Result<Response> {
+        if let Some(body) = self.cache.get(&url, self.offline).await {
+            return Ok(Response {
+                status: StatusCode::OK,
+                body,
+            });
+        }
+        let res = self.inner.get(url.clone()).await?;
+        if res.status.is_success() {
+            // The response is still good even if it could not be cached.
+            let _ = self.cache.put(&url, &res.body).await;
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use crate::{
+        game, global_games, profile, profile_games,
+        testutils::{self, MockTransport},
+        types::profile::ProfileId,
+        Client,
+    };
+
+    use super::*;
+
+    /// Returns an empty directory for a test to use as a cache.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("prelate-rs-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn client(cache: Cache, transport: Arc<MockTransport>) -> Client {
+        Client::with_transport(Client::builder().with_cache(Some(cache)), transport)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cache_hit() {
+        let dir = temp_dir("hit");
+        let cache = Cache::new(&dir).with_policy(Some(CachePolicy::All));
+        let transport = Arc::new(MockTransport::fixture(include_str!(
+            "../testdata/profile/housedhorse.json"
+        )));
+
+        for _ in 0..2 {
+            let client = client(cache.clone(), transport.clone());
+            let p = profile(3176)
+                .with_client(Some(client))
+                .get()
+                .await
+                .expect("profile should be ok");
+            assert_eq!(ProfileId::from(3176), p.profile_id);
+        }
+        assert_eq!(1, transport.requests().len());
+
+        cache.clear().await.expect("cache should clear");
+        let client = client(cache.clone(), transport.clone());
+        profile(3176)
+            .with_client(Some(client))
+            .get()
+            .await
+            .expect("profile should be ok");
+        assert_eq!(2, transport.requests().len());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cache_policy() {
+        let dir = temp_dir("policy");
+        let transport = Arc::new(MockTransport::fixture(include_str!(
+            "../testdata/profile/housedhorse.json"
+        )));
+
+        let client = client(Cache::new(&dir), transport.clone());
+        for _ in 0..2 {
+            profile(3176)
+                .with_client(Some(client.clone()))
+                .get()
+                .await
+                .expect("profile should be ok");
+        }
+        assert_eq!(2, transport.requests().len());
+        assert!(Cache::new(&dir).entries().await.unwrap().is_empty());
+
+        // (path, current season, expected with the default policy, with finished pages)
+        let cases = [
+            ("leaderboards/rm_solo?season=5", None, true, true),
+            ("leaderboards/rm_solo?season=8", None, false, false),
+            ("leaderboards/rm_solo?season=8", Some(9), true, true),
+            ("leaderboards/rm_solo?season=9", Some(9), false, false),
+            ("leaderboards/rm_solo", None, false, false),
+            ("players/3176/games/56783543", None, true, true),
+            ("players/3176/games", None, false, true),
+            ("games?leaderboard=rm_1v1", None, false, true),
+            ("players/3176", None, false, false),
+        ];
+        for (path, current_season, want_default, want_pages) in cases {
+            let url = format!("https://aoe4world.com/api/v0/{path}")
+                .parse()
+                .unwrap();
+            let cache = Cache::new(&dir).with_current_season(current_season);
+            let case = format!("{path}, {current_season:?}");
+            assert_eq!(want_default, cache.is_cacheable(&url), "{case}");
+            let cache = cache.with_policy(Some(CachePolicy::FinishedGamePages));
+            assert_eq!(
+                want_pages,
+                cache.is_cacheable(&url),
+                "{case}, finished pages"
+            );
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cache_finished_game_pages() {
+        // Pages of games are only cached when opted into.
+        let cases = [(None, 2), (Some(CachePolicy::FinishedGamePages), 1)];
+        for (policy, want_requests) in cases {
+            let dir = temp_dir(&format!("finished-{}", policy.is_some()));
+            let transport = Arc::new(MockTransport::fixture(include_str!(
+                "../testdata/games/neptune.json"
+            )));
+            let client = client(Cache::new(&dir).with_policy(policy), transport.clone());
+
+            for _ in 0..2 {
+                let games: Vec<_> = profile_games(4635035)
+                    .with_client(Some(client.clone()))
+                    .get(50)
+                    .await
+                    .expect("query should succeed")
+                    .try_collect()
+                    .await
+                    .expect("games should be ok");
+                assert_eq!(50, games.len());
+            }
+            assert_eq!(want_requests, transport.requests().len(), "{policy:?}");
+
+            // Nothing is written without the policy, so the directory may not exist.
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cache_single_game() {
+        let body = include_str!("../testdata/games/neptune_game_from_list.json");
+        for (ongoing, want_requests) in [(false, 1), (true, 2)] {
+            let dir = temp_dir(&format!("game-{ongoing}"));
+            let body = body.replace(r#""ongoing": false"#, &format!(r#""ongoing": {ongoing}"#));
+            let transport = Arc::new(MockTransport::new(move |_| testutils::ok(body.clone())));
+            let client = client(Cache::new(&dir), transport.clone());
+            for _ in 0..2 {
+                game(4635035, 56783543)
+                    .with_client(Some(client.clone()))
+                    .get()
+                    .await
+                    .expect("game should be ok");
+            }
+            assert_eq!(
+                want_requests,
+                transport.requests().len(),
+                "ongoing {ongoing}"
+            );
+
+            // Nothing is written for an ongoing game, so the directory may not exist.
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cache_skips_ongoing_games() {
+        let dir = temp_dir("ongoing");
+        let cache = Cache::new(&dir).with_policy(Some(CachePolicy::All));
+        let transport = Arc::new(MockTransport::fixture(include_str!(
+            "../testdata/games/global.json"
+        )));
+        let client = client(cache.clone(), transport.clone());
+
+        for _ in 0..2 {
+            let games: Vec<_> = global_games()
+                .with_client(Some(client.clone()))
+                .get(50)
+                .await
+                .expect("query should succeed")
+                .try_collect()
+                .await
+                .expect("games should be ok");
+            assert_eq!(50, games.len());
+        }
+        assert_eq!(2, transport.requests().len());
+        assert!(cache.entries().await.unwrap().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cache_eviction() {
+        let dir = temp_dir("eviction");
+        let body = include_str!("../testdata/profile/housedhorse.json");
+        let cache = Cache::new(&dir)
+            .with_policy(Some(CachePolicy::All))
+            .with_max_size(Some(2 * body.len() as u64 + 200));
+
+        let now = SystemTime::now();
+        for id in 0..3 {
+            let url = format!("https://aoe4world.com/api/v0/players/{id}")
+                .parse()
+                .unwrap();
+            cache.put(&url, body).await.expect("put should succeed");
+            // Entries written later are more recently used, whatever the mtime granularity.
+            let age = std::time::Duration::from_secs(10 - id);
+            set_modified(&cache.path(&url), now - age).expect("mtime should be set");
+        }
+
+        let entries = cache.entries().await.unwrap();
+        assert_eq!(2, entries.len());
+        let first = "https://aoe4world.com/api/v0/players/0".parse().unwrap();
+        assert_eq!(None, cache.get(&first, false).await);
+        let last = "https://aoe4world.com/api/v0/players/2".parse().unwrap();
+        assert_eq!(Some(body), cache.get(&last, false).await.as_deref());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_offline_replay() {
+        let dir = temp_dir("offline");
+        let transport = Arc::new(MockTransport::fixture(include_str!(
+            "../testdata/games/neptune.json"
+        )));
+
+        // Record the pages online.
+        let cache = Cache::new(&dir).with_policy(Some(CachePolicy::All));
+        let recorded: Vec<_> = profile_games(4635035)
+            .with_client(Some(client(cache, transport.clone())))
+            .get(100)
+            .await
+            .expect("query should succeed")
+            .try_collect()
+            .await
+            .expect("games should be ok");
+        assert_eq!(100, recorded.len());
+        assert_eq!(2, transport.requests().len());
+
+        // Replay them offline, where every cached response is served whatever the policy.
+        let client = Client::with_transport(
+            Client::builder()
+                .with_cache(Some(Cache::new(&dir)))
+                .with_offline(Some(true)),
+            transport.clone(),
+        );
+        let replayed: Vec<_> = profile_games(4635035)
+            .with_client(Some(client.clone()))
+            .get(100)
+            .await
+            .expect("query should succeed")
+            .try_collect()
+            .await
+            .expect("games should be replayed");
+        assert_eq!(recorded, replayed);
+        assert_eq!(2, transport.requests().len());
+
+        let err = profile(4635035)
+            .with_client(Some(client))
+            .get()
+            .await
+            .expect_err("profile was not recorded");
+        assert!(matches!(
+            err.downcast_ref::<crate::Error>(),
+            Some(crate::Error::Offline { .. })
+        ));
+        assert_eq!(2, transport.requests().len());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,1451 @@
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+//! HTTP client shared by queries.
+
+// Clippy complains about needless update in derived setters.
+#![allow(clippy::needless_update)]
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use derive_setters::Setters;
+use futures::{
+    future::{BoxFuture, Shared},
+    FutureExt,
+};
+use reqwest::{header, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize};
+use tokio::{sync::Semaphore, time::Instant};
+use url::Url;
+
+#[cfg(feature = "disk-cache")]
+use crate::cache::{Cache, CachingTransport};
+use crate::Error;
+
+#[cfg(not(any(feature = "native-tls", feature = "rustls")))]
+compile_error!("prelate-rs requires either the `native-tls` or the `rustls` feature to be enabled");
+
+/// Returns a [`reqwest::ClientBuilder`] using the configured TLS backend.
+///
+/// The TLS backend is selected with the `native-tls` (default) and `rustls` features. Since
+/// features are additive, both may end up enabled in a workspace; `rustls` takes precedence
+/// in that case. Proxies set in the `HTTP_PROXY`, `HTTPS_PROXY` and `ALL_PROXY` environment
+/// variables are honored unless an explicit proxy is configured.
+fn http_client_builder() -> reqwest::ClientBuilder {
+    let builder = reqwest::Client::builder();
+    #[cfg(feature = "rustls")]
+    let builder = builder.use_rustls_tls();
+    #[cfg(all(feature = "native-tls", not(feature = "rustls")))]
+    let builder = builder.use_native_tls();
+    builder
+}
+
+/// Returns the [`reqwest::Client`] shared by all queries, or the error it failed to build
+/// with.
+///
+/// The client is constructed lazily on first use so that every request reuses the same
+/// connection pool.
+pub(crate) fn http_client() -> &'static Result<reqwest::Client, String> {
+    static CLIENT: OnceLock<Result<reqwest::Client, String>> = OnceLock::new();
+    CLIENT.get_or_init(|| build_http_client(http_client_builder()))
+}
+
+/// Builds the client from `builder`, keeping the error as a message to report on each
+/// request rather than panicking.
+fn build_http_client(builder: reqwest::ClientBuilder) -> Result<reqwest::Client, String> {
+    builder.build().map_err(|e| format!("{:#}", anyhow!(e)))
+}
+
+#[cfg(feature = "blocking")]
+tokio::task_local! {
+    /// Set while a blocking query runs, so that queries not given a client use
+    /// [`Client::blocking`] rather than the client shared with async code.
+    pub(crate) static BLOCKING: ();
+}
+
+/// A raw response from the API.
+#[derive(Clone)]
+pub(crate) struct Response {
+    pub status: StatusCode,
+    pub body: String,
+}
+
+/// Sends requests to the API. Abstracted so that tests can serve canned responses.
+#[async_trait]
+pub(crate) trait Transport: Send + Sync {
+    /// Sends a GET request to `url`.
+    async fn get(&self, url: Url) -> Result<Response>;
+
+    /// Sends a GET request to `url` with an `If-None-Match` header if `etag` is set,
+    /// returning the response along with its `ETag` header, if any. A `304 Not Modified`
+    /// response means the resource still matches `etag`.
+    ///
+    /// Transports which do not support conditional requests ignore `etag`.
+    async fn get_conditional(
+        &self,
+        url: Url,
+        _etag: Option<String>,
+    ) -> Result<(Response, Option<String>)> {
+        Ok((self.get(url).await?, None))
+    }
+}
+
+/// User agent sent with every request unless another is set with
+/// [`ClientBuilder::with_user_agent`].
+const DEFAULT_USER_AGENT: &str = concat!("prelate-rs/", env!("CARGO_PKG_VERSION"));
+
+/// [`Transport`] backed by a [`reqwest::Client`].
+struct HttpTransport {
+    /// Client sending the requests: the shared one unless a proxy is configured. Holds the
+    /// error instead if the client failed to build, which every request then fails with.
+    client: Result<reqwest::Client, String>,
+    /// Value of the `User-Agent` header.
+    user_agent: String,
+}
+
+impl HttpTransport {
+    /// Returns a transport sending requests with the client built by `builder`.
+    fn new(builder: reqwest::ClientBuilder, user_agent: String) -> Self {
+        Self {
+            client: build_http_client(builder),
+            user_agent,
+        }
+    }
+
+    /// Returns a GET request to `url`, with an `If-None-Match` header if `etag` is set.
+    fn request(&self, url: Url, etag: Option<String>) -> Result<reqwest::RequestBuilder> {
+        let client = match &self.client {
+            Ok(client) => client,
+            Err(e) => bail!("failed to build the HTTP client: {e}"),
+        };
+        let mut req = client.get(url).header(header::USER_AGENT, &self.user_agent);
+        if let Some(etag) = etag {
+            req = req.header(header::IF_NONE_MATCH, etag);
+        }
+        Ok(req)
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn get(&self, url: Url) -> Result<Response> {
+        let (res, _) = self.get_conditional(url, None).await?;
+        Ok(res)
+    }
+
+    async fn get_conditional(
+        &self,
+        url: Url,
+        etag: Option<String>,
+    ) -> Result<(Response, Option<String>)> {
+        let res = self.request(url, etag)?.send().await?;
+        let status = res.status();
+        let etag = res
+            .headers()
+            .get(header::ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(str::to_string);
+        let body = res.text().await?;
+        Ok((Response { status, body }, etag))
+    }
+}
+
+/// Maximum number of responses a [`ConditionalTransport`] remembers.
+const CONDITIONAL_RESPONSES_CAPACITY: usize = 1000;
+
+/// Responses remembered by a [`ConditionalTransport`]. At most
+/// [`CONDITIONAL_RESPONSES_CAPACITY`] are kept, dropping the least recently used first.
+#[derive(Default)]
+struct ConditionalResponses {
+    /// Responses by URL, along with their `ETag` and when they were last used.
+    entries: HashMap<Url, (String, Response, u64)>,
+    /// Incremented on each use, to order entries by recency.
+    clock: u64,
+}
+
+impl ConditionalResponses {
+    /// Returns the `ETag` and response stored for `url`, marking it as recently used.
+    fn get(&mut self, url: &Url) -> Option<(String, Response)> {
+        self.clock += 1;
+        let (etag, res, used) = self.entries.get_mut(url)?;
+        *used = self.clock;
+        Some((etag.clone(), res.clone()))
+    }
+
+    /// Stores `res` for `url`, dropping the least recently used response if full.
+    fn put(&mut self, url: Url, etag: String, res: Response) {
+        self.clock += 1;
+        if self.entries.len() >= CONDITIONAL_RESPONSES_CAPACITY && !self.entries.contains_key(&url)
+        {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, _, used))| *used)
+                .map(|(url, _)| url.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(url, (etag, res, self.clock));
+    }
+}
+
+/// [`Transport`] which remembers the `ETag` of each successful response, and sends it with
+/// the next request for the same URL. If the server answers `304 Not Modified`, the
+/// remembered response is returned in its place.
+struct ConditionalTransport {
+    inner: Arc<dyn Transport>,
+    responses: Mutex<ConditionalResponses>,
+}
+
+#[async_trait]
+impl Transport for ConditionalTransport {
+    async fn get(&self, url: Url) -> Result<Response> {
+        let stored = self.responses.lock().unwrap().get(&url);
+        let etag = stored.as_ref().map(|(etag, _)| etag.clone());
+        let (res, etag) = self.inner.get_conditional(url.clone(), etag).await?;
+        match (res.status, stored, etag) {
+            (StatusCode::NOT_MODIFIED, Some((_, stored)), _) => Ok(stored),
+            (status, _, Some(etag)) if status.is_success() => {
+                self.responses.lock().unwrap().put(url, etag, res.clone());
+                Ok(res)
+            }
+            _ => Ok(res),
+        }
+    }
+}
+
+/// [`Transport`] used in offline mode, which fails every request unless its endpoint is
+/// bypassed.
+struct OfflineTransport {
+    inner: Arc<dyn Transport>,
+    bypass: Vec<Endpoint>,
+}
+
+#[async_trait]
+impl Transport for OfflineTransport {
+    async fn get(&self, url: Url) -> Result<Response> {
+        if self.bypass.iter().any(|e| e.matches(&url)) {
+            return self.inner.get(url).await;
+        }
+        Err(Error::Offline { url }.into())
+    }
+}
+
+/// Successful responses kept in memory for a limited time, keyed by URL.
+struct MemoryCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<Url, (Instant, Response)>>,
+}
+
+impl MemoryCache {
+    /// Returns the response for `url`, unless there is none or it has expired.
+    fn get(&self, url: &Url) -> Option<Response> {
+        let entries = self.entries.lock().unwrap();
+        let (stored_at, res) = entries.get(url)?;
+        (stored_at.elapsed() < self.ttl).then(|| res.clone())
+    }
+
+    /// Stores `res` for `url`, dropping expired responses.
+    fn put(&self, url: Url, res: Response) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (stored_at, _)| stored_at.elapsed() < self.ttl);
+        entries.insert(url, (Instant::now(), res));
+    }
+}
+
+/// [`Transport`] which serves responses from a [`MemoryCache`] until they expire.
+struct MemoryCachingTransport {
+    inner: Arc<dyn Transport>,
+    cache: Arc<MemoryCache>,
+}
+
+#[async_trait]
+impl Transport for MemoryCachingTransport {
+    async fn get(&self, url: Url) -> Result<Response> {
+        if let Some(res) = self.cache.get(&url) {
+            return Ok(res);
+        }
+        let res = self.inner.get(url.clone()).await?;
+        if res.status.is_success() {
+            self.cache.put(url, res.clone());
+        }
+        Ok(res)
+    }
+}
+
+/// An endpoint of the aoe4world API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Endpoint {
+    /// `/players/{profile_id}`
+    Profile,
+    /// `/players/{profile_id}/games`
+    ProfileGames,
+    /// `/players/{profile_id}/games/{game_id}`
+    Game,
+    /// `/players/search`
+    Search,
+    /// `/games`
+    Games,
+    /// `/leaderboards/{leaderboard}`
+    Leaderboard,
+}
+
+impl Endpoint {
+    /// Does `url` point to this endpoint?
+    pub(crate) fn matches(&self, url: &Url) -> bool {
+        let Some(path) = url.path().strip_prefix("/api/v0/") else {
+            return false;
+        };
+        let segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+        match (self, segments.as_slice()) {
+            (Endpoint::Search, ["players", "search"]) => true,
+            (Endpoint::Profile, ["players", id]) => *id != "search",
+            (Endpoint::ProfileGames, ["players", _, "games"]) => true,
+            (Endpoint::Game, ["players", _, "games", _]) => true,
+            (Endpoint::Games, ["games"]) => true,
+            (Endpoint::Leaderboard, ["leaderboards", _]) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Default maximum number of items a single query may request.
+const DEFAULT_MAX_ITEMS: usize = 100_000;
+
+/// Configuration for a [`Client`].
+#[derive(Setters, Default, Clone)]
+#[setters(prefix = "with_")]
+#[setters(into)]
+pub struct ClientBuilder {
+    /// Maximum number of requests in flight at once, across every query using the
+    /// [`Client`]. Unlimited by default.
+    max_in_flight: Option<usize>,
+    /// Maximum number of items a single query may request. Queries asking for more fail
+    /// with [`Error::LimitTooLarge`] rather than paginating for a very long time. Defaults
+    /// to 100,000.
+    max_items: Option<usize>,
+    /// Cache used to serve responses across runs. Not used by default.
+    #[cfg(feature = "disk-cache")]
+    cache: Option<Cache>,
+    /// Never touch the network, serving responses exclusively from the cache (see
+    /// `with_cache`, behind the `disk-cache` feature). Requests for anything not cached fail
+    /// with [`Error::Offline`]. Disabled by default.
+    offline: Option<bool>,
+    /// Endpoints which may still be requested over the network in offline mode.
+    offline_bypass: Option<Vec<Endpoint>>,
+    /// Keep successful responses in memory for this long, and serve identical requests
+    /// from memory until then, e.g. to poll a leaderboard without hitting the API every
+    /// time. Responses are keyed by URL, so a query must be built the same way to hit the
+    /// cache. Entries can be dropped early with [`Client::invalidate`] and
+    /// [`Client::clear_memory_cache`]. Not used by default.
+    memory_cache_ttl: Option<Duration>,
+    /// Send conditional requests: the `ETag` of each successful response is remembered and
+    /// sent in an `If-None-Match` header when the same URL is requested again. If the
+    /// server answers `304 Not Modified`, the remembered response is reused, which saves
+    /// transferring it again. The last responses for up to 1000 URLs are kept in memory,
+    /// dropping the least recently used ones first. Disabled by default.
+    conditional_requests: Option<bool>,
+    /// `User-Agent` header sent with every request, identifying the application to
+    /// aoe4world. Defaults to `prelate-rs/{version}`.
+    user_agent: Option<String>,
+    /// Proxy every request is sent through. Requests made without one go through the
+    /// proxies set in the `HTTP_PROXY`, `HTTPS_PROXY` and `ALL_PROXY` environment variables,
+    /// if any. Setting a proxy gives the [`Client`] its own connection pool rather than
+    /// sharing one with other clients.
+    proxy: Option<reqwest::Proxy>,
+}
+
+impl ClientBuilder {
+    /// Builds the [`Client`].
+    ///
+    /// Should its HTTP client fail to build, e.g. because the TLS backend cannot
+    /// initialize, every request fails with the error.
+    pub fn build(self) -> Client {
+        let transport = self.http_transport(http_client());
+        self.build_with_transport(Arc::new(transport))
+    }
+
+    /// Returns the transport sending requests over HTTP: through `shared` unless a proxy
+    /// is set, in which case it gets an HTTP client of its own.
+    fn http_transport(&self, shared: &Result<reqwest::Client, String>) -> HttpTransport {
+        let user_agent = self
+            .user_agent
+            .clone()
+            .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string());
+        match self.proxy.clone() {
+            Some(proxy) => HttpTransport::new(http_client_builder().proxy(proxy), user_agent),
+            None => HttpTransport {
+                client: shared.clone(),
+                user_agent,
+            },
+        }
+    }
+
+    /// Builds the [`Client`] with a connection pool of its own, rather than the one shared
+    /// by other clients. Like [`Self::build`], requests fail if the pool fails to build.
+    #[cfg(feature = "blocking")]
+    fn build_with_own_pool(self) -> Client {
+        let user_agent = self
+            .user_agent
+            .clone()
+            .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string());
+        let builder = match self.proxy.clone() {
+            Some(proxy) => http_client_builder().proxy(proxy),
+            None => http_client_builder(),
+        };
+        self.build_with_transport(Arc::new(HttpTransport::new(builder, user_agent)))
+    }
+
+    /// Builds the [`Client`], sending requests through `transport`.
+    fn build_with_transport(self, transport: Arc<dyn Transport>) -> Client {
+        let transport: Arc<dyn Transport> = match self.conditional_requests.unwrap_or_default() {
+            true => Arc::new(ConditionalTransport {
+                inner: transport,
+                responses: Default::default(),
+            }),
+            false => transport,
+        };
+        let offline = self.offline.unwrap_or_default();
+        let transport: Arc<dyn Transport> = match offline {
+            true => Arc::new(OfflineTransport {
+                inner: transport,
+                bypass: self.offline_bypass.unwrap_or_default(),
+            }),
+            false => transport,
+        };
+        #[cfg(feature = "disk-cache")]
+        let transport: Arc<dyn Transport> = match self.cache {
+            Some(cache) => Arc::new(CachingTransport {
+                inner: transport,
+                cache,
+                offline,
+            }),
+            None => transport,
+        };
+        let memory_cache = self.memory_cache_ttl.map(|ttl| {
+            Arc::new(MemoryCache {
+                ttl,
+                entries: Default::default(),
+            })
+        });
+        let transport: Arc<dyn Transport> = match memory_cache {
+            Some(ref cache) => Arc::new(MemoryCachingTransport {
+                inner: transport,
+                cache: cache.clone(),
+            }),
+            None => transport,
+        };
+        Client {
+            transport,
+            in_flight: self.max_in_flight.map(|n| Arc::new(Semaphore::new(n))),
+            pending: Default::default(),
+            max_items: self.max_items.unwrap_or(DEFAULT_MAX_ITEMS),
+            memory_cache,
+        }
+    }
+}
+
+/// Client used by queries to talk to the aoe4world API.
+///
+/// Queries use a default client unless one is passed with their `with_client` setter.
+/// Cloning a client is cheap, and clones share state such as the limit on requests in
+/// flight.
+///
+/// Identical requests made concurrently through the same client are coalesced: while a
+/// request for a URL is in flight, later requests for that URL wait for its response
+/// instead of sending their own. Responses, including errors, are not kept around once the
+/// request completes, unless the client has a memory cache (see
+/// [`ClientBuilder::with_memory_cache_ttl`]).
+///
+/// # Examples
+///
+/// In the following example, we bound the number of concurrent requests across several
+/// queries to 4, regardless of how many pages each query prefetches:
+/// ```rust
+/// # #[cfg(feature = "test-api")]
+/// # tokio_test::block_on(async {
+/// use prelate_rs::{
+///     futures::{self, StreamExt},
+///     global_games, leaderboard,
+///     types::leaderboards::Leaderboard,
+///     Client,
+/// };
+///
+/// let client = Client::builder().with_max_in_flight(Some(4)).build();
+///
+/// let games = global_games()
+///     .with_client(Some(client.clone()))
+///     .get(200)
+///     .await
+///     .expect("query should succeed");
+/// let entries = leaderboard(Leaderboard::RmSolo)
+///     .with_client(Some(client))
+///     .get(200)
+///     .await
+///     .expect("query should succeed");
+/// let (games, entries) = futures::join!(
+///     games.collect::<Vec<_>>(),
+///     entries.collect::<Vec<_>>()
+/// );
+/// # })
+/// ```
+#[derive(Clone)]
+pub struct Client {
+    transport: Arc<dyn Transport>,
+    in_flight: Option<Arc<Semaphore>>,
+    pending: Arc<Mutex<HashMap<Url, PendingResponse>>>,
+    max_items: usize,
+    memory_cache: Option<Arc<MemoryCache>>,
+}
+
+/// A response to a request in flight, which may be awaited by several callers.
+type PendingResponse = Shared<BoxFuture<'static, Result<Response, Arc<anyhow::Error>>>>;
+
+impl Default for Client {
+    /// Returns the client shared by all queries that aren't given one explicitly. Blocking
+    /// queries share one of their own instead.
+    fn default() -> Self {
+        #[cfg(feature = "blocking")]
+        if BLOCKING.try_with(|_| ()).is_ok() {
+            return Self::blocking();
+        }
+        static CLIENT: OnceLock<Client> = OnceLock::new();
+        CLIENT
+            .get_or_init(|| ClientBuilder::default().build())
+            .clone()
+    }
+}
+
+impl Client {
+    /// Returns a [`ClientBuilder`] to configure a new client.
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// Returns the client shared by blocking queries that aren't given one explicitly.
+    ///
+    /// Pooled connections and coalesced requests belong to the runtime they were started
+    /// on, and blocking queries run on a runtime of their own, so this client shares
+    /// neither with the default one.
+    #[cfg(feature = "blocking")]
+    fn blocking() -> Self {
+        static CLIENT: OnceLock<Client> = OnceLock::new();
+        CLIENT
+            .get_or_init(|| ClientBuilder::default().build_with_own_pool())
+            .clone()
+    }
+
+    /// Returns a client which sends requests through `transport`.
+    #[cfg(test)]
+    pub(crate) fn with_transport(builder: ClientBuilder, transport: Arc<dyn Transport>) -> Self {
+        builder.build_with_transport(transport)
+    }
+
+    /// Drops the response for `url` from the memory cache, if any, so that the next request
+    /// for it goes to the API. `url` is as returned by a query's `build_url`, plus the
+    /// `limit` and `page` parameters for paginated queries.
+    pub fn invalidate(&self, url: &Url) {
+        if let Some(ref cache) = self.memory_cache {
+            cache.entries.lock().unwrap().remove(url);
+        }
+    }
+
+    /// Drops every response from the memory cache.
+    pub fn clear_memory_cache(&self) {
+        if let Some(ref cache) = self.memory_cache {
+            cache.entries.lock().unwrap().clear();
+        }
+    }
+
+    /// Maximum number of items a single query may request.
+    pub(crate) fn max_items(&self) -> usize {
+        self.max_items
+    }
+
+    /// Sends a GET request to `url` and deserializes the JSON response.
+    ///
+    /// With the `tracing` feature, the request is wrapped in a `request` span recording the
+    /// URL, status code and elapsed time.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "request",
+            level = "debug",
+            skip(self),
+            fields(%url, status, elapsed_ms)
+        )
+    )]
+    pub(crate) async fn get_json<T: DeserializeOwned>(&self, url: Url) -> Result<T> {
+        let start = Instant::now();
+        let res = self.get(url.clone()).await;
+        record_response(&res, start.elapsed());
+        let res = res?;
+        decode_json(url, res.status, &res.body)
+    }
+
+    /// Sends a GET request to `url`, or waits for the response to an identical request that
+    /// is already in flight.
+    async fn get(&self, url: Url) -> Result<Response> {
+        let response = self
+            .pending
+            .lock()
+            .unwrap()
+            .entry(url.clone())
+            .or_insert_with(|| self.request(url))
+            .clone();
+        response.await.map_err(|e| clone_error(&e))
+    }
+
+    /// Returns a future which sends a GET request to `url` and then removes itself from the
+    /// pending requests.
+    fn request(&self, url: Url) -> PendingResponse {
+        let transport = self.transport.clone();
+        let in_flight = self.in_flight.clone();
+        let pending = self.pending.clone();
+        async move {
+            let res = async {
+                let _permit = match in_flight {
+                    Some(ref in_flight) => Some(in_flight.acquire().await?),
+                    None => None,
+                };
+                transport.get(url.clone()).await
+            }
+            .await;
+            pending.lock().unwrap().remove(&url);
+            res.map_err(Arc::new)
+        }
+        .boxed()
+        .shared()
+    }
+}
+
+/// Clones an error shared between coalesced requests. Errors from this crate keep their
+/// type so that they can still be downcast; others are converted into a message.
+fn clone_error(e: &anyhow::Error) -> anyhow::Error {
+    match e.downcast_ref::<Error>() {
+        Some(e) => e.clone().into(),
+        None => anyhow!("{e:#}"),
+    }
+}
+
+/// Error message in the body of a response the API rejected.
+#[derive(Deserialize)]
+struct ApiError {
+    error: String,
+}
+
+/// Records the outcome of a request on the current span and emits an event for it. Does
+/// nothing without the `tracing` feature.
+fn record_response(res: &Result<Response>, elapsed: Duration) {
+    #[cfg(feature = "tracing")]
+    {
+        let span = tracing::Span::current();
+        span.record("elapsed_ms", elapsed.as_millis() as u64);
+        match res {
+            Ok(res) => {
+                span.record("status", res.status.as_u16());
+                tracing::debug!(status = res.status.as_u16(), ?elapsed, "response received");
+            }
+            Err(e) => tracing::warn!(error = %e, ?elapsed, "request failed"),
+        }
+    }
+    #[cfg(not(feature = "tracing"))]
+    let _ = (res, elapsed);
+}
+
+/// Deserializes a JSON response body, checking the status code first.
+///
+/// Returns [`Error::NotFound`] for a 404, since the body is then an error message rather
+/// than the expected type. Other client errors, and error messages sent with a successful
+/// status, become [`Error::Api`]. Server errors are left untyped, so that they are retried,
+/// and so are rate limits and timeouts, which are client errors that may pass on their own.
+fn decode_json<T: DeserializeOwned>(url: Url, status: StatusCode, body: &str) -> Result<T> {
+    if status == StatusCode::NOT_FOUND {
+        return Err(Error::NotFound { url }.into());
+    }
+    let api_error = |message: Option<String>| Error::Api {
+        url: url.clone(),
+        status: status.as_u16(),
+        message: message.unwrap_or_else(|| body.to_string()),
+    };
+    let message = || serde_json::from_str::<ApiError>(body).ok().map(|e| e.error);
+    let transient = [StatusCode::TOO_MANY_REQUESTS, StatusCode::REQUEST_TIMEOUT];
+    if status.is_client_error() && !transient.contains(&status) {
+        return Err(api_error(message()).into());
+    }
+    if !status.is_success() {
+        bail!("request to {url} failed with status {status}: {body}");
+    }
+    match serde_json::from_str(body) {
+        Ok(value) => Ok(value),
+        Err(e) => match message() {
+            Some(message) => Err(api_error(Some(message)).into()),
+            None => Err(e.into()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use crate::{
+        error::optional,
+        global_games, profile,
+        testutils::{ok, MockTransport},
+        types::profile::Profile,
+    };
+
+    use super::*;
+
+    fn profile_url() -> Url {
+        "https://aoe4world.com/api/v0/players/123456789"
+            .parse()
+            .unwrap()
+    }
+
+    /// Serves a profile tagged with the current version, answering `304 Not Modified` when
+    /// the request's `If-None-Match` matches it.
+    #[derive(Default)]
+    struct EtagTransport {
+        version: Mutex<u32>,
+        sent_etags: Mutex<Vec<Option<String>>>,
+    }
+
+    #[async_trait]
+    impl Transport for EtagTransport {
+        async fn get(&self, url: Url) -> Result<Response> {
+            let (res, _) = self.get_conditional(url, None).await?;
+            Ok(res)
+        }
+
+        async fn get_conditional(
+            &self,
+            _url: Url,
+            etag: Option<String>,
+        ) -> Result<(Response, Option<String>)> {
+            self.sent_etags.lock().unwrap().push(etag.clone());
+            let current = format!("\"v{}\"", self.version.lock().unwrap());
+            if etag.as_ref() == Some(&current) {
+                let res = Response {
+                    status: StatusCode::NOT_MODIFIED,
+                    body: String::new(),
+                };
+                return Ok((res, Some(current)));
+            }
+            let res = ok(include_str!("../testdata/profile/housedhorse.json"));
+            Ok((res, Some(current)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conditional_requests() {
+        for conditional in [false, true] {
+            let transport = Arc::new(EtagTransport::default());
+            let client = Client::with_transport(
+                Client::builder().with_conditional_requests(Some(conditional)),
+                transport.clone(),
+            );
+            let get = || profile(3176).with_client(Some(client.clone())).get();
+
+            let first = get().await.expect("query should succeed");
+            let second = get().await.expect("not modified should reuse the response");
+            assert_eq!(first, second);
+            *transport.version.lock().unwrap() += 1;
+            get().await.expect("query should succeed");
+            get().await.expect("not modified should reuse the response");
+
+            let sent = transport.sent_etags.lock().unwrap().clone();
+            let want = match conditional {
+                false => vec![None; 4],
+                true => vec![
+                    None,
+                    Some("\"v0\"".to_string()),
+                    Some("\"v0\"".to_string()),
+                    Some("\"v1\"".to_string()),
+                ],
+            };
+            assert_eq!(want, sent, "conditional {conditional}");
+        }
+    }
+
+    #[test]
+    fn test_conditional_responses_capacity() {
+        let url = |i: usize| -> Url {
+            format!("https://aoe4world.com/api/v0/players/{i}")
+                .parse()
+                .unwrap()
+        };
+        let mut responses = ConditionalResponses::default();
+        for i in 0..CONDITIONAL_RESPONSES_CAPACITY {
+            responses.put(url(i), format!("\"v{i}\""), ok("{}"));
+        }
+        // Using the first response leaves the second as the least recently used.
+        assert!(responses.get(&url(0)).is_some());
+        responses.put(
+            url(CONDITIONAL_RESPONSES_CAPACITY),
+            "\"v\"".into(),
+            ok("{}"),
+        );
+        assert_eq!(CONDITIONAL_RESPONSES_CAPACITY, responses.entries.len());
+        assert!(responses.get(&url(0)).is_some());
+        assert!(responses.get(&url(1)).is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_conditional_requests_without_etag() {
+        // Responses without an ETag are passed through, and errors are never remembered.
+        let transport = Arc::new(MockTransport::new(|url| {
+            if url.path().ends_with("/1") {
+                Response {
+                    status: StatusCode::NOT_FOUND,
+                    body: include_str!("../testdata/profile/not_found.json").into(),
+                }
+            } else {
+                ok(include_str!("../testdata/profile/housedhorse.json"))
+            }
+        }));
+        let client = Client::with_transport(
+            Client::builder().with_conditional_requests(Some(true)),
+            transport.clone(),
+        );
+        for _ in 0..2 {
+            profile(3176)
+                .with_client(Some(client.clone()))
+                .get()
+                .await
+                .expect("query should succeed");
+            profile(1)
+                .with_client(Some(client.clone()))
+                .get()
+                .await
+                .expect_err("query should fail");
+        }
+        assert_eq!(4, transport.requests().len());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_memory_cache() {
+        let ttl = Duration::from_secs(60);
+        let transport = Arc::new(MockTransport::new(|url| {
+            if url.path().ends_with("/1") {
+                Response {
+                    status: StatusCode::NOT_FOUND,
+                    body: include_str!("../testdata/profile/not_found.json").into(),
+                }
+            } else {
+                ok(include_str!("../testdata/profile/housedhorse.json"))
+            }
+        }));
+        let client = Client::with_transport(
+            Client::builder().with_memory_cache_ttl(Some(ttl)),
+            transport.clone(),
+        );
+        let get = |id: u64| profile(id).with_client(Some(client.clone())).get();
+
+        let first = get(3176).await.expect("query should succeed");
+        let second = get(3176).await.expect("query should succeed");
+        assert_eq!(first, second);
+        assert_eq!(
+            1,
+            transport.requests().len(),
+            "second request should be cached"
+        );
+
+        // Expired responses are fetched again.
+        tokio::time::advance(ttl).await;
+        get(3176).await.expect("query should succeed");
+        assert_eq!(
+            2,
+            transport.requests().len(),
+            "expired response should be refetched"
+        );
+
+        // Errors are not cached.
+        for _ in 0..2 {
+            get(1).await.expect_err("query should fail");
+        }
+        assert_eq!(4, transport.requests().len(), "errors should not be cached");
+
+        let url: Url = "https://aoe4world.com/api/v0/players/3176".parse().unwrap();
+        client.invalidate(&url);
+        get(3176).await.expect("query should succeed");
+        get(3176).await.expect("query should succeed");
+        assert_eq!(
+            5,
+            transport.requests().len(),
+            "invalidated response should be refetched"
+        );
+
+        client.clear_memory_cache();
+        get(3176).await.expect("query should succeed");
+        assert_eq!(
+            6,
+            transport.requests().len(),
+            "cleared response should be refetched"
+        );
+
+        // Clients without a memory cache always send requests.
+        let transport = Arc::new(MockTransport::fixture(include_str!(
+            "../testdata/profile/housedhorse.json"
+        )));
+        let client = Client::with_transport(Client::builder(), transport.clone());
+        for _ in 0..2 {
+            profile(3176)
+                .with_client(Some(client.clone()))
+                .get()
+                .await
+                .expect("query should succeed");
+        }
+        client.clear_memory_cache();
+        assert_eq!(2, transport.requests().len());
+    }
+
+    #[test]
+    fn test_tls_backend() {
+        // CI runs this with each backend on its own and with both, where rustls wins.
+        http_client_builder()
+            .build()
+            .expect("TLS backend should initialize");
+    }
+
+    #[test]
+    fn test_http_client_is_shared() {
+        assert!(std::ptr::eq(http_client(), http_client()));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_client_is_separate() {
+        let blocking = crate::blocking::block_on(async { Client::default() });
+        assert!(!Arc::ptr_eq(
+            &Client::default().transport,
+            &blocking.transport
+        ));
+        let again = crate::blocking::block_on(async { Client::default() });
+        assert!(Arc::ptr_eq(&blocking.transport, &again.transport));
+    }
+
+    #[test]
+    fn test_default_client_is_shared() {
+        assert!(Arc::ptr_eq(
+            &Client::default().pending,
+            &Client::default().pending
+        ));
+    }
+
+    #[test]
+    fn test_decode_json_not_found() {
+        let body = include_str!("../testdata/profile/not_found.json");
+        let err = decode_json::<Profile>(profile_url(), StatusCode::NOT_FOUND, body)
+            .expect_err("404 should be an error");
+        assert_eq!(
+            Some(&Error::NotFound { url: profile_url() }),
+            err.downcast_ref::<Error>()
+        );
+    }
+
+    #[test]
+    fn test_decode_json_server_error() {
+        let err = decode_json::<Profile>(profile_url(), StatusCode::BAD_GATEWAY, "")
+            .expect_err("502 should be an error");
+        assert!(err.downcast_ref::<Error>().is_none());
+    }
+
+    #[test]
+    fn test_optional_not_found() {
+        let body = include_str!("../testdata/profile/not_found.json");
+        let res = decode_json::<Profile>(profile_url(), StatusCode::NOT_FOUND, body);
+        assert_eq!(None, optional(res).expect("404 should be Ok(None)"));
+
+        let body = include_str!("../testdata/profile/housedhorse.json");
+        let res = decode_json::<Profile>(profile_url(), StatusCode::OK, body);
+        assert!(optional(res).expect("200 should be Ok").is_some());
+    }
+
+    /// Runs several concurrent paginated queries through one client and returns the high
+    /// water mark of requests in flight.
+    async fn max_in_flight(builder: ClientBuilder) -> usize {
+        let transport = Arc::new(MockTransport::fixture(include_str!(
+            "../testdata/games/global.json"
+        )));
+        let client = &Client::with_transport(builder, transport.clone());
+
+        // Distinct filters keep identical requests from being coalesced.
+        let queries = (0..4u64).map(|i| async move {
+            global_games()
+                .with_profile_ids(Some(vec![i.into()]))
+                .with_client(Some(client.clone()))
+                .get(200)
+                .await
+                .expect("query should succeed")
+                .collect::<Vec<_>>()
+                .await
+        });
+        for games in futures::future::join_all(queries).await {
+            assert_eq!(200, games.len());
+        }
+
+        assert_eq!(16, transport.requests().len());
+        transport.max_in_flight()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_max_in_flight() {
+        let max = max_in_flight(Client::builder().with_max_in_flight(Some(3))).await;
+        assert!(max <= 3, "{max} requests in flight");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_max_in_flight_unlimited() {
+        let max = max_in_flight(Client::builder()).await;
+        assert!(max > 3, "{max} requests in flight");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_coalesce_requests() {
+        let transport = Arc::new(MockTransport::fixture(include_str!(
+            "../testdata/profile/housedhorse.json"
+        )));
+        let client = Client::with_transport(Client::builder(), transport.clone());
+
+        let queries = (0..10).map(|_| profile(3176).with_client(Some(client.clone())).get());
+        for profile in futures::future::join_all(queries).await {
+            profile.expect("profile should be ok");
+        }
+        assert_eq!(1, transport.requests().len());
+        assert!(client.pending.lock().unwrap().is_empty());
+
+        profile(3176)
+            .with_client(Some(client.clone()))
+            .get()
+            .await
+            .expect("profile should be ok");
+        assert_eq!(2, transport.requests().len());
+    }
+
+    #[test]
+    fn test_decode_json_errors() {
+        let url = profile_url();
+        let api_error = |status: StatusCode, body: &str| {
+            decode_json::<Profile>(url.clone(), status, body)
+                .unwrap_err()
+                .downcast::<Error>()
+                .ok()
+        };
+
+        assert_eq!(
+            Some(Error::Api {
+                url: url.clone(),
+                status: 400,
+                message: "bad request".into()
+            }),
+            api_error(StatusCode::BAD_REQUEST, "bad request")
+        );
+        assert_eq!(
+            Some(Error::Api {
+                url: url.clone(),
+                status: 422,
+                message: "Invalid season".into()
+            }),
+            api_error(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                r#"{"error": "Invalid season"}"#
+            )
+        );
+        assert_eq!(
+            Some(Error::Api {
+                url: url.clone(),
+                status: 200,
+                message: "Invalid season".into()
+            }),
+            api_error(StatusCode::OK, r#"{"error": "Invalid season"}"#)
+        );
+        // Bodies which are neither the expected type nor an error message fail to decode.
+        assert_eq!(None, api_error(StatusCode::OK, r#"{"name": 1}"#));
+        // Rate limits and timeouts stay untyped, so that pages are retried.
+        assert_eq!(None, api_error(StatusCode::TOO_MANY_REQUESTS, "slow down"));
+        assert_eq!(None, api_error(StatusCode::REQUEST_TIMEOUT, "timed out"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_coalesce_requests_errors_not_cached() {
+        let transport = Arc::new(MockTransport::new(|url| {
+            if url.path().ends_with("/1") {
+                Response {
+                    status: StatusCode::NOT_FOUND,
+                    body: include_str!("../testdata/profile/not_found.json").into(),
+                }
+            } else {
+                ok(include_str!("../testdata/profile/housedhorse.json"))
+            }
+        }));
+        let client = Client::with_transport(Client::builder(), transport.clone());
+
+        let queries = (0..10).map(|_| profile(1).with_client(Some(client.clone())).get());
+        for profile in futures::future::join_all(queries).await {
+            let err = profile.expect_err("profile should not be found");
+            assert!(matches!(
+                err.downcast_ref::<Error>(),
+                Some(Error::NotFound { .. })
+            ));
+        }
+        assert_eq!(1, transport.requests().len());
+
+        profile(1)
+            .with_client(Some(client.clone()))
+            .get()
+            .await
+            .expect_err("profile should not be found");
+        assert_eq!(2, transport.requests().len());
+    }
+
+    /// Fails the first request with a transport error, then serves a profile.
+    #[derive(Default)]
+    struct FlakyTransport {
+        requests: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Transport for FlakyTransport {
+        async fn get(&self, _url: Url) -> Result<Response> {
+            let n = self
+                .requests
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            tokio::time::sleep(MockTransport::DELAY).await;
+            if n == 0 {
+                bail!("connection reset");
+            }
+            Ok(ok(include_str!("../testdata/profile/housedhorse.json")))
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_coalesce_requests_transport_errors_not_cached() {
+        let transport = Arc::new(FlakyTransport::default());
+        let client = Client::with_transport(Client::builder(), transport.clone());
+        let requests = || transport.requests.load(std::sync::atomic::Ordering::SeqCst);
+
+        let queries = (0..10).map(|_| profile(3176).with_client(Some(client.clone())).get());
+        for profile in futures::future::join_all(queries).await {
+            let err = profile.expect_err("transport error should be shared");
+            assert!(err.to_string().contains("connection reset"), "{err}");
+        }
+        assert_eq!(1, requests());
+        assert!(client.pending.lock().unwrap().is_empty());
+
+        profile(3176)
+            .with_client(Some(client.clone()))
+            .get()
+            .await
+            .expect("a new request should be sent after the error");
+        assert_eq!(2, requests());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_offline() {
+        let transport = Arc::new(MockTransport::fixture(include_str!(
+            "../testdata/profile/housedhorse.json"
+        )));
+        let client = Client::with_transport(
+            Client::builder().with_offline(Some(true)),
+            transport.clone(),
+        );
+
+        let err = profile(3176)
+            .with_client(Some(client))
+            .get()
+            .await
+            .expect_err("request should fail offline");
+        assert_eq!(
+            Some(&Error::Offline {
+                url: "https://aoe4world.com/api/v0/players/3176".parse().unwrap()
+            }),
+            err.downcast_ref::<Error>()
+        );
+        assert!(transport.requests().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_offline_bypass() {
+        let transport = Arc::new(MockTransport::fixture(include_str!(
+            "../testdata/profile/housedhorse.json"
+        )));
+        let client = Client::with_transport(
+            Client::builder()
+                .with_offline(Some(true))
+                .with_offline_bypass(Some(vec![Endpoint::Profile])),
+            transport.clone(),
+        );
+
+        profile(3176)
+            .with_client(Some(client.clone()))
+            .get()
+            .await
+            .expect("bypassed endpoint should be requested");
+        assert_eq!(1, transport.requests().len());
+
+        let games = global_games()
+            .with_client(Some(client))
+            .get(10)
+            .await
+            .expect("query should start")
+            .collect::<Vec<_>>()
+            .await;
+        games[0]
+            .as_ref()
+            .expect_err("games should not be requested offline");
+        assert_eq!(1, transport.requests().len());
+    }
+
+    #[test]
+    fn test_endpoint_matches() {
+        let cases = [
+            ("players/3176", Endpoint::Profile),
+            ("players/3176/games", Endpoint::ProfileGames),
+            ("players/3176/games/56783543", Endpoint::Game),
+            ("players/search?query=abc", Endpoint::Search),
+            ("games?leaderboard=rm_1v1", Endpoint::Games),
+            ("leaderboards/rm_solo", Endpoint::Leaderboard),
+        ];
+        for (path, endpoint) in cases {
+            let url: Url = format!("https://aoe4world.com/api/v0/{path}")
+                .parse()
+                .unwrap();
+            for (_, other) in cases {
+                assert_eq!(endpoint == other, other.matches(&url), "{other:?} {url}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_user_agent() {
+        use std::{
+            io::{BufRead, BufReader, Write},
+            net::TcpListener,
+        };
+
+        let cases = [
+            (None, concat!("prelate-rs/", env!("CARGO_PKG_VERSION"))),
+            (Some("my-app/1.0".to_string()), "my-app/1.0"),
+        ];
+        for (user_agent, want) in cases {
+            // A proxy which answers the first request with an empty profile, and hands
+            // back the user agent it received.
+            let listener = TcpListener::bind("127.0.0.1:0").expect("should bind");
+            let addr = listener.local_addr().expect("should have an address");
+            let proxy = std::thread::spawn(move || {
+                let (stream, _) = listener.accept().expect("should accept");
+                let mut reader = BufReader::new(&stream);
+                let mut user_agent = None;
+                let mut line = String::new();
+                while line != "\r\n" {
+                    line.clear();
+                    reader.read_line(&mut line).expect("should read");
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("user-agent") {
+                            user_agent = Some(value.trim().to_string());
+                        }
+                    }
+                }
+                (&stream)
+                    .write_all(
+                        b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{}",
+                    )
+                    .expect("should write");
+                user_agent
+            });
+
+            let client = Client::builder()
+                .with_user_agent(user_agent)
+                .with_proxy(reqwest::Proxy::all(format!("http://{addr}")).expect("valid proxy"))
+                .build();
+            let url: Url = "http://aoe4world.invalid/api/v0/players/1".parse().unwrap();
+            client
+                .transport
+                .get(url)
+                .await
+                .expect("request should go through the proxy");
+            assert_eq!(
+                Some(want.to_string()),
+                proxy.join().expect("proxy should not panic")
+            );
+        }
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let req = HttpTransport {
+            client: http_client().clone(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+        }
+        .request(profile_url(), Some("\"v1\"".to_string()))
+        .expect("client should be built")
+        .build()
+        .expect("request should build");
+        assert_eq!("\"v1\"", req.headers()[header::IF_NONE_MATCH]);
+    }
+
+    #[tokio::test]
+    async fn test_http_client_error() {
+        // A shared client that failed to build is reported on each request rather than
+        // panicking.
+        let builder = Client::builder();
+        let transport = builder.http_transport(&Err("TLS backend unavailable".into()));
+        let client = Client::with_transport(builder, Arc::new(transport));
+        for _ in 0..2 {
+            let err = profile(3176)
+                .with_client(Some(client.clone()))
+                .get()
+                .await
+                .expect_err("request should fail");
+            assert!(
+                format!("{err:#}")
+                    .contains("failed to build the HTTP client: TLS backend unavailable"),
+                "{err:#}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_proxy() {
+        use std::{
+            io::{BufRead, BufReader, Write},
+            net::TcpListener,
+        };
+
+        // A proxy which answers the first request with an empty profile, and hands back
+        // the request line it received.
+        let listener = TcpListener::bind("127.0.0.1:0").expect("should bind");
+        let addr = listener.local_addr().expect("should have an address");
+        let proxy = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("should accept");
+            let mut reader = BufReader::new(&stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).expect("should read");
+            let mut line = String::new();
+            while line != "\r\n" {
+                line.clear();
+                reader.read_line(&mut line).expect("should read");
+            }
+            (&stream)
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{}")
+                .expect("should write");
+            request_line
+        });
+
+        let client = Client::builder()
+            .with_proxy(reqwest::Proxy::all(format!("http://{addr}")).expect("valid proxy"))
+            .build();
+        let url: Url = "http://aoe4world.invalid/api/v0/players/1".parse().unwrap();
+        let res = client
+            .transport
+            .get(url.clone())
+            .await
+            .expect("request should go through the proxy");
+        assert_eq!(StatusCode::OK, res.status);
+        assert_eq!("{}", res.body);
+        assert_eq!(
+            format!("GET {url} HTTP/1.1\r\n"),
+            proxy.join().expect("proxy should not panic")
+        );
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test(start_paused = true)]
+    async fn test_tracing() {
+        use std::{
+            fmt::Debug,
+            sync::atomic::{AtomicU64, Ordering},
+        };
+
+        use tracing::{
+            field::{Field, Visit},
+            span, Event, Metadata, Subscriber,
+        };
+
+        use crate::{profile_games, PageFailure};
+
+        /// Records the names of new spans and the messages of events, in order.
+        #[derive(Default, Clone)]
+        struct Recorder {
+            log: Arc<Mutex<Vec<String>>>,
+            next_id: Arc<AtomicU64>,
+        }
+
+        struct Message(String);
+
+        impl Visit for Message {
+            fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+                if field.name() == "message" {
+                    self.0 = format!("{value:?}");
+                }
+            }
+        }
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+                let name = span.metadata().name();
+                self.log.lock().unwrap().push(format!("span {name}"));
+                span::Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+            }
+
+            fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+            fn event(&self, event: &Event<'_>) {
+                let mut message = Message(String::new());
+                event.record(&mut message);
+                self.log.lock().unwrap().push(message.0);
+            }
+
+            fn enter(&self, _: &span::Id) {}
+
+            fn exit(&self, _: &span::Id) {}
+        }
+
+        let recorder = Recorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        let transport = Arc::new(MockTransport::new(|_| Response {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            body: String::new(),
+        }));
+        let client = Client::with_transport(Client::builder(), transport);
+        let games: Vec<_> = profile_games(3176)
+            .with_page_failure(Some(PageFailure::SkipAfterRetries(1)))
+            .with_client(Some(client))
+            .get(10)
+            .await
+            .expect("query should succeed")
+            .collect()
+            .await;
+        assert!(games[0].is_err());
+
+        let log = recorder.log.lock().unwrap().clone();
+        assert_eq!(
+            vec![
+                "span page",
+                "span request",
+                "response received",
+                "retrying page",
+                "span request",
+                "response received",
+                "skipping page",
+            ],
+            log
+        );
+    }
+}
@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+//! Errors that callers may want to handle explicitly.
+
+use std::fmt::Display;
+
+use anyhow::Result;
+use url::Url;
+
+/// Errors that callers may want to handle explicitly.
+///
+/// Queries return an [`anyhow::Error`], which can be converted back into this type with
+/// [`anyhow::Error::downcast_ref`]:
+/// ```rust
+/// # #[cfg(feature = "test-api")]
+/// # tokio_test::block_on(async {
+/// use prelate_rs::{profile, Error};
+///
+/// match profile(u64::MAX).get().await {
+///     Ok(profile) => println!("found {}", profile.name),
+///     Err(e) => match e.downcast_ref::<Error>() {
+///         Some(Error::NotFound { .. }) => println!("no such player"),
+///         _ => println!("something else went wrong: {e}"),
+///     },
+/// }
+/// # })
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// The requested resource does not exist.
+    NotFound {
+        /// URL of the request.
+        url: Url,
+    },
+    /// A query asked for more items than the [`crate::Client`] allows.
+    LimitTooLarge {
+        /// Number of items requested.
+        requested: usize,
+        /// Maximum number of items allowed.
+        max: usize,
+    },
+    /// A query was cancelled through its cancellation token.
+    Cancelled,
+    /// The [`crate::Client`] is offline and the response was not cached.
+    Offline {
+        /// URL of the request.
+        url: Url,
+    },
+    /// The API rejected the request with a client error, e.g. because a parameter such as
+    /// the season is invalid. Such requests are not retried.
+    Api {
+        /// URL of the request.
+        url: Url,
+        /// HTTP status code of the response.
+        status: u16,
+        /// Error message returned by the API, or the response body if it has none.
+        message: String,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::NotFound { url } => write!(f, "not found: {url}"),
+            Error::LimitTooLarge { requested, max } => {
+                write!(
+                    f,
+                    "requested {requested} items, but at most {max} are allowed"
+                )
+            }
+            Error::Cancelled => write!(f, "query was cancelled"),
+            Error::Offline { url } => write!(f, "not cached while offline: {url}"),
+            Error::Api {
+                url,
+                status,
+                message,
+            } => write!(f, "request to {url} failed with status {status}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Converts an [`Error::NotFound`] into `Ok(None)`, passing through any other result.
+pub(crate) fn optional<T>(res: Result<T>) -> Result<Option<T>> {
+    match res {
+        Ok(t) => Ok(Some(t)),
+        Err(e) if matches!(e.downcast_ref::<Error>(), Some(Error::NotFound { .. })) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
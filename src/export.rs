@@ -0,0 +1,175 @@
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+//! Export of data returned by the API to other formats, behind the `csv` feature.
+
+use std::{io::Write, pin::pin};
+
+use anyhow::Result;
+use futures::{Stream, TryStreamExt};
+use serde::Serialize;
+
+use crate::types::{
+    civilization::Civilization,
+    games::{Game, GameKind, GameResult},
+    leaderboards::Leaderboard,
+    maps::Map,
+    profile::ProfileId,
+};
+
+/// A row of the CSV written by [`write_games_csv`]: one player in one game.
+#[derive(Serialize)]
+struct GameRow<'a> {
+    game_id: u32,
+    started_at: Option<chrono::DateTime<chrono::Utc>>,
+    duration: Option<u32>,
+    map: Option<&'a Map>,
+    kind: Option<GameKind>,
+    leaderboard: Option<Leaderboard>,
+    team: usize,
+    name: &'a str,
+    profile_id: ProfileId,
+    civilization: Option<Civilization>,
+    result: Option<GameResult>,
+    rating: Option<u32>,
+    rating_diff: Option<i64>,
+    mmr: Option<i64>,
+    mmr_diff: Option<i64>,
+}
+
+/// Writes a stream of games to `writer` as CSV, with one row per player in each game.
+///
+/// The first row is a header naming the columns: `game_id`, `started_at`, `duration`, `map`,
+/// `kind`, `leaderboard`, `team`, `name`, `profile_id`, `civilization`, `result`, `rating`,
+/// `rating_diff`, `mmr` and `mmr_diff`. `team` is the index of the player's team in the
+/// game, and missing values are left empty. Stops at the first error in the stream, after
+/// flushing the rows written so far.
+pub async fn write_games_csv<W: Write>(
+    games: impl Stream<Item = Result<Game>>,
+    writer: W,
+) -> Result<()> {
+    let mut games = pin!(games);
+    let mut csv = csv::Writer::from_writer(writer);
+    let res = async {
+        while let Some(game) = games.try_next().await? {
+            for (team, players) in game.teams.iter().enumerate() {
+                for player in players {
+                    csv.serialize(GameRow {
+                        game_id: game.game_id,
+                        started_at: game.started_at,
+                        duration: game.duration,
+                        map: game.map.as_ref(),
+                        kind: game.kind,
+                        leaderboard: game.leaderboard,
+                        team,
+                        name: &player.name,
+                        profile_id: player.profile_id,
+                        civilization: player.civilization,
+                        result: player.result,
+                        rating: player.rating,
+                        rating_diff: player.rating_diff,
+                        mmr: player.mmr,
+                        mmr_diff: player.mmr_diff,
+                    })?;
+                }
+            }
+        }
+        Ok(())
+    }
+    .await;
+    csv.flush()?;
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use crate::{pagination::Paginated, types::games::ProfileGames};
+
+    use super::*;
+
+    fn games() -> Vec<Game> {
+        let games: ProfileGames =
+            serde_json::from_str(include_str!("../testdata/games/neptune.json"))
+                .expect("should deserialize");
+        games.data()
+    }
+
+    #[tokio::test]
+    async fn test_write_games_csv() {
+        let games = games();
+        let mut buf = Vec::new();
+        write_games_csv(
+            futures::stream::iter(games.clone().into_iter().map(Ok)),
+            &mut buf,
+        )
+        .await
+        .expect("should succeed");
+
+        let mut reader = csv::Reader::from_reader(buf.as_slice());
+        assert_eq!(
+            vec![
+                "game_id",
+                "started_at",
+                "duration",
+                "map",
+                "kind",
+                "leaderboard",
+                "team",
+                "name",
+                "profile_id",
+                "civilization",
+                "result",
+                "rating",
+                "rating_diff",
+                "mmr",
+                "mmr_diff"
+            ],
+            reader
+                .headers()
+                .expect("should have headers")
+                .iter()
+                .collect::<Vec<_>>()
+        );
+        let records: Vec<_> = reader
+            .records()
+            .collect::<Result<_, _>>()
+            .expect("records should parse");
+        let players: Vec<_> = games
+            .iter()
+            .flat_map(|game| game.players().map(move |player| (game, player)))
+            .collect();
+        assert_eq!(players.len(), records.len());
+        for (record, (game, player)) in records.iter().zip(players) {
+            assert_eq!(game.game_id.to_string(), record[0]);
+            assert_eq!(player.name, record[7]);
+            assert_eq!(player.profile_id.to_string(), record[8]);
+            let civ = player.civilization.map(|c| c.to_string());
+            assert_eq!(civ.as_deref().unwrap_or_default(), &record[9]);
+            let diff = player.rating_diff.map(|d| d.to_string());
+            assert_eq!(diff.as_deref().unwrap_or_default(), &record[12]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_games_csv_error() {
+        let games = games();
+        let stream = futures::stream::iter(
+            [
+                Ok(games[0].clone()),
+                Err(anyhow!("boom")),
+                Ok(games[1].clone()),
+            ]
+            .into_iter(),
+        );
+        let mut buf = Vec::new();
+        let err = write_games_csv(stream, &mut buf)
+            .await
+            .expect_err("should fail");
+        assert_eq!("boom", err.to_string());
+
+        // Rows written before the error are kept.
+        let mut reader = csv::Reader::from_reader(buf.as_slice());
+        assert_eq!(games[0].players().count(), reader.records().count());
+    }
+}
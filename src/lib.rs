@@ -7,21 +7,46 @@
 //!
 //! [aoe4world]: https://aoe4world.com/api
 
+pub mod analysis;
+#[cfg(feature = "csv")]
+pub mod export;
 pub mod types;
 
+#[cfg(feature = "blocking")]
+mod blocking;
+#[cfg(feature = "disk-cache")]
+mod cache;
+mod client;
+mod error;
 mod pagination;
 
 #[cfg(test)]
 mod testutils;
 
-use query::{GlobalGamesQuery, LeaderboardQuery, ProfileGamesQuery, ProfileQuery, SearchQuery};
-use types::{leaderboards::Leaderboard, profile::ProfileId};
+use anyhow::Result;
+use futures::{Stream, TryStreamExt};
+use query::{
+    GameQuery, GlobalGamesQuery, LeaderboardQuery, ProfileGamesQuery, ProfileQuery, ProfilesQuery,
+    ResumeQuery, SearchQuery,
+};
+use types::{
+    leaderboards::Leaderboard,
+    profile::{Profile, ProfileId},
+};
+
+#[cfg(feature = "disk-cache")]
+pub use cache::{Cache, CachePolicy};
+pub use client::{Client, ClientBuilder, Endpoint};
+pub use error::Error;
+pub use pagination::{Cursor, CursorHandle, Page, PageFailure, PaginationMeta};
 
 // Rexports
 pub use chrono;
 pub use futures;
 pub use isocountry::CountryCode;
 pub use strum;
+pub use tokio_util::sync::CancellationToken;
+pub use url::Url;
 
 /// Returns a [`ProfileQuery`]. Used to get profile for a player.
 ///
@@ -31,12 +56,117 @@ pub fn profile(profile_id: impl Into<ProfileId>) -> ProfileQuery {
     ProfileQuery::default().with_profile_id(Some(profile_id.into()))
 }
 
+/// Resolves a player's [`Profile`] from their Steam ID.
+///
+/// Uses the `/players/search` endpoint, which matches Steam IDs as well as names. Returns
+/// `None` if no player has this Steam ID.
+///
+/// # Params
+/// - `steam_id` is the 64-bit Steam ID of the player (e.g. `"76561198094298920"`).
+pub async fn profile_by_steam_id(steam_id: impl AsRef<str>) -> Result<Option<Profile>> {
+    let steam_id = steam_id.as_ref();
+    let profiles = search(steam_id).get(STEAM_ID_SEARCH_LIMIT).await?;
+    find_steam_id(profiles, steam_id).await
+}
+
+/// Maximum number of search results to scan when resolving a Steam ID.
+const STEAM_ID_SEARCH_LIMIT: usize = 10;
+
+/// Returns the first profile in `profiles` with the given Steam ID.
+async fn find_steam_id(
+    profiles: impl Stream<Item = Result<Profile>>,
+    steam_id: &str,
+) -> Result<Option<Profile>> {
+    futures::pin_mut!(profiles);
+    while let Some(profile) = profiles.try_next().await? {
+        if profile.steam_id.as_deref() == Some(steam_id) {
+            return Ok(Some(profile));
+        }
+    }
+    Ok(None)
+}
+
+/// Returns a [`ProfilesQuery`]. Used to get profiles for several players at once.
+///
+/// # Params
+/// - `profile_ids` are the aoe4world IDs of the players.
+///
+/// # Examples
+///
+/// In the following example, we fetch the profiles of a team roster concurrently. Profiles
+/// are yielded in the same order as the given IDs:
+/// ```rust
+/// # #[cfg(feature = "test-api")]
+/// # tokio_test::block_on(async {
+/// use prelate_rs::{futures::StreamExt, profiles};
+///
+/// let stream = profiles([3176, 10433860])
+///     .get()
+///     .await
+///     .expect("query should succeed");
+/// let profiles = stream.collect::<Vec<_>>().await;
+///
+/// for profile in profiles {
+///     // Do something with each profile.
+/// # profile.expect("profile should be valid");
+/// }
+/// # })
+/// ```
+pub fn profiles(profile_ids: impl IntoIterator<Item = impl Into<ProfileId>>) -> ProfilesQuery {
+    ProfilesQuery::default().with_profile_ids(Some(
+        profile_ids.into_iter().map(Into::into).collect::<Vec<_>>(),
+    ))
+}
+
 /// Returns a [`ProfileGamesQuery`]. Used to query the `/profile/{profile_id}/games` endpoint.
 ///
 /// # Params
 /// - `profile_id` is aoe4world the ID of the player whose games should be searched.
 pub fn profile_games(profile_id: impl Into<ProfileId>) -> ProfileGamesQuery {
-    ProfileGamesQuery::default().with_profile_id(Some(profile_id.into()))
+    ProfileGamesQuery::default().with_profile_id(profile_id)
+}
+
+/// Returns a [`GameQuery`]. Used to fetch a single game from the
+/// `/players/{profile_id}/games/{game_id}` endpoint, e.g. to get the final result of a game
+/// seen while it was ongoing.
+///
+/// # Params
+/// - `profile_id` is the aoe4world ID of a player in the game.
+/// - `game_id` is the ID of the game.
+pub fn game(profile_id: impl Into<ProfileId>, game_id: u32) -> GameQuery {
+    GameQuery::default()
+        .with_profile_id(Some(profile_id.into()))
+        .with_game_id(Some(game_id))
+}
+
+/// Returns a [`ProfileGamesQuery`] for all games where player `a` played against player `b`.
+///
+/// # Params
+/// - `a` is the aoe4world ID of the player whose games should be searched.
+/// - `b` is the aoe4world ID of the opponent.
+///
+/// # Examples
+///
+/// In the following example, we tally the wins and losses of one player against another
+/// over their 100 most recent games with [`analysis::record`]:
+/// ```rust
+/// # #[cfg(feature = "test-api")]
+/// # tokio_test::block_on(async {
+/// use prelate_rs::{analysis, head_to_head};
+///
+/// let stream = head_to_head(3176, 10433860)
+///     .get(100)
+///     .await
+///     .expect("query should succeed");
+/// let record = analysis::record(stream, 3176)
+///     .await
+///     .expect("games should be valid");
+///
+/// println!("{} wins, {} losses", record.wins, record.losses);
+/// # })
+/// ```
+pub fn head_to_head(a: impl Into<ProfileId>, b: impl Into<ProfileId>) -> ProfileGamesQuery {
+    profile_games(a).with_opponent_profile_id(Some(b.into()))
 }
 
 /// Returns a [`GlobalGamesQuery`]. Used to query the `/games` endpoint.
@@ -52,7 +182,7 @@ pub fn profile_games(profile_id: impl Into<ProfileId>) -> ProfileGamesQuery {
 /// use prelate_rs::{futures::StreamExt, global_games, types::games::GameKind};
 ///
 /// let stream = global_games()
-///     .with_leaderboard(Some(vec![GameKind::Rm1v1]))
+///     .with_game_kinds([GameKind::Rm1v1])
 ///     .get(100)
 ///     .await
 ///     .expect("query should succeed");
@@ -102,7 +232,7 @@ pub fn global_games() -> GlobalGamesQuery {
 /// ## Exact Search
 ///
 /// In the following example, we search for the player who matches exactly the
-/// search query `"[DEBILS] HousedHorse"`:
+/// search query `"[DEBILS] HousedHorse"`. [`find_player`] does the same in one call:
 /// ```rust
 /// # #[cfg(feature = "test-api")]
 /// # tokio_test::block_on(async {
@@ -123,7 +253,81 @@ pub fn global_games() -> GlobalGamesQuery {
 /// # })
 /// ```
 pub fn search(query: impl AsRef<str>) -> SearchQuery {
-    SearchQuery::default().with_query(Some(query.as_ref().to_string()))
+    SearchQuery::default().with_query(query.as_ref())
+}
+
+/// Resolves a player's nickname to their [`Profile`].
+///
+/// Uses an exact [`search`], keeping only profiles whose name is exactly `name`. If several
+/// players share the name, the one who played most recently, per
+/// [`Profile::last_game_at`], is returned, and the first in search order on ties. Returns
+/// `None` if no player has this name. Use [`find_players_exact`] to get all of them.
+///
+/// # Params
+/// - `name` is the player's username.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "test-api")]
+/// # tokio_test::block_on(async {
+/// use prelate_rs::find_player;
+///
+/// let profile = find_player("[DEBILS] HousedHorse")
+///     .await
+///     .expect("query should succeed")
+///     .expect("player should exist");
+/// # })
+/// ```
+pub async fn find_player(name: impl AsRef<str>) -> Result<Option<Profile>> {
+    Ok(most_recent(find_players_exact(name).await?))
+}
+
+/// Returns the [`Profile`] of every player whose name is exactly `name`, in search order.
+///
+/// # Params
+/// - `name` is the players' username.
+pub async fn find_players_exact(name: impl AsRef<str>) -> Result<Vec<Profile>> {
+    let name = name.as_ref();
+    let profiles = search(name)
+        .with_exact(Some(true))
+        .get(EXACT_SEARCH_LIMIT)
+        .await?;
+    exact_matches(profiles, name).await
+}
+
+/// Maximum number of search results to scan when resolving a nickname.
+const EXACT_SEARCH_LIMIT: usize = 50;
+
+/// Returns the profiles in `profiles` named exactly `name`.
+async fn exact_matches(
+    profiles: impl Stream<Item = Result<Profile>>,
+    name: &str,
+) -> Result<Vec<Profile>> {
+    profiles
+        .try_filter(|profile| futures::future::ready(profile.name == name))
+        .try_collect()
+        .await
+}
+
+/// Returns the profile that played most recently, the first one on ties.
+fn most_recent(profiles: Vec<Profile>) -> Option<Profile> {
+    profiles.into_iter().reduce(|best, profile| {
+        if profile.last_game_at > best.last_game_at {
+            profile
+        } else {
+            best
+        }
+    })
+}
+
+/// Returns a [`ResumeQuery`]. Used to resume a games query from a [`Cursor`], as saved from
+/// the [`CursorHandle`] of its stream.
+///
+/// # Params
+/// - `cursor` is the position to resume from.
+pub fn resume(cursor: Cursor) -> ResumeQuery {
+    ResumeQuery::default().with_cursor(Some(cursor))
 }
 
 /// Returns a [`ProfileGamesQuery`]. Used to query the `/leaderboards/{leaderboard}` endpoint.
@@ -131,7 +335,7 @@ pub fn search(query: impl AsRef<str>) -> SearchQuery {
 /// # Params
 /// - `leaderboard` is the leaderboard to fetch.
 pub fn leaderboard(leaderboard: impl Into<Leaderboard>) -> LeaderboardQuery {
-    LeaderboardQuery::default().with_leaderboard(Some(leaderboard.into()))
+    LeaderboardQuery::default().with_leaderboard(leaderboard)
 }
 
 pub mod query {
@@ -144,29 +348,72 @@ pub mod query {
     #![allow(clippy::needless_update)]
 
     use anyhow::{bail, Result};
+    use std::{
+        ops::{Bound, Range, RangeBounds},
+        sync::Arc,
+        time::Duration,
+    };
+
     use derive_setters::Setters;
-    use futures::{Stream, StreamExt};
+    use futures::{Stream, StreamExt, TryStreamExt};
     use isocountry::CountryCode;
-    use itertools::join;
+    use itertools::{join, Either};
+    use tokio_util::sync::CancellationToken;
     use url::Url;
 
     use crate::{
-        pagination::{PaginatedRequest, PaginationClient},
+        client::{Client, Endpoint},
+        error::optional,
+        pagination::{
+            all_of, cancellable, Cursor, CursorHandle, Filter, Page, PageFailure, PaginatedRequest,
+            PaginationClient, PaginationMeta, MAX_COUNT_PER_PAGE, MAX_GAMES_COUNT_PER_PAGE,
+        },
         types::{
-            games::{Game, GameKind, GamesOrder, GlobalGames, ProfileGames},
+            games::{
+                Game, GameKind, GameState, GamesOrder, GlobalGames, OrderDirection, Platform,
+                ProfileGames,
+            },
             leaderboards::{Leaderboard, LeaderboardEntry, LeaderboardPages},
-            profile::{Profile, ProfileId},
+            maps::{Map, MapType},
+            profile::{GameModeStats, Profile, ProfileId},
             search::SearchResults,
         },
     };
 
+    /// Stands in for a required parameter that has not been set yet, e.g. in
+    /// `ProfileGamesQuery<Missing>`. Queries cannot be sent until their required parameters
+    /// are set, which turns them into their default type, e.g. [`ProfileGamesQuery`].
+    ///
+    /// Such queries keep their other parameters in a separate options struct, so that
+    /// setting the required one only moves that struct into the new type rather than
+    /// copying every field.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Missing;
+
     /// Constructs a query for the `/players/{profile_id}/games` endpoint.
+    ///
+    /// Paging works as described under [paging options](crate::Page#paging-options).
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct ProfileGamesQuery<P = ProfileId> {
+        /// [`ProfileId`] to query. Required, set with [`Self::with_profile_id`].
+        profile_id: P,
+        /// Every other parameter, see [`Missing`].
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        options: ProfileGamesOptions,
+    }
+
+    /// Options of a [`ProfileGamesQuery`] other than its required [`ProfileId`].
     #[derive(Setters, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[setters(prefix = "with_")]
     #[setters(into)]
-    pub struct ProfileGamesQuery {
-        /// [`ProfileId`] to query.
-        profile_id: Option<ProfileId>,
+    #[setters(generate_delegates(
+        ty = "ProfileGamesQuery<P>",
+        generics = "<P>",
+        field = "options"
+    ))]
+    struct ProfileGamesOptions {
         /// Filter by [`Leaderboard`] .
         game_kind: Option<Vec<GameKind>>,
         /// Filter by [`Leaderboard`]. Same as [`GameKind`] but supports [`Leaderboard::RmSolo`] and [`Leaderboard::RmTeam`].
@@ -175,65 +422,379 @@ pub mod query {
         opponent_profile_id: Option<ProfileId>,
         /// Filter over a list of opponent profile IDs.
         opponent_profile_ids: Option<Vec<ProfileId>>,
-        /// Filter by time played since a specific date.
+        /// Filter by time played since a specific date, i.e. games started after it.
         since: Option<chrono::DateTime<chrono::Utc>>,
+        /// Only keep games started before a specific date. Together with `since`, this
+        /// bounds a window of games. The API cannot filter on it, so this is a client-side
+        /// filter: games are newest first, so those started later are fetched and dropped
+        /// before the first matching one. Games without a start date are dropped.
+        until: Option<chrono::DateTime<chrono::Utc>>,
+        /// Filter by games whose record was updated since a specific date. Unlike `since`,
+        /// this includes games started earlier that have since finished and had their result
+        /// written, which makes it the right filter for incremental syncs.
+        updated_since: Option<chrono::DateTime<chrono::Utc>>,
+        /// Include games played on the player's linked alternate accounts. The queried
+        /// profile ID then does not appear in the teams of those games, so
+        /// [`Game::player`] returns `None` for it.
+        include_alts: Option<bool>,
+        /// Order of the games, most recent first. Ordering by [`GamesOrder::UpdatedAt`] pairs
+        /// with `updated_since` and `with_stop_when` to stop an incremental sync early.
+        order: Option<GamesOrder>,
+        /// Direction of the order. Defaults to [`OrderDirection::Desc`], most recent first.
+        /// The API cannot order games oldest first, so [`OrderDirection::Asc`] fetches pages
+        /// from the last one back, and fails if the API does not report the total number of
+        /// games. Filters and `stop_when` apply oldest first. It is only supported by `get`
+        /// and `get_all`; other methods return an error.
+        direction: Option<OrderDirection>,
+        /// Only keep games in one of these [`GameState`]s. The API cannot filter on the
+        /// state, so this is a client-side filter: other games are dropped from the stream.
+        /// The limit then counts matching games, while `page` and `offset` still count every
+        /// game.
+        state: Option<Vec<GameState>>,
+        /// Only keep games played in one of these seasons. The API cannot filter on the
+        /// season, so like `state` this is a client-side filter. Games without a season
+        /// are dropped.
+        season: Option<Vec<u32>>,
+        /// Only keep games played on one of these [`Map`]s. The API cannot filter on the
+        /// map, so like `state` this is a client-side filter. Set with [`Self::with_maps`].
+        #[setters(skip)]
+        maps: Option<Vec<Map>>,
+        /// Only keep games played on a map of one of these [`MapType`]s, e.g. water maps.
+        /// A client-side filter like `maps`. Set with [`Self::with_map_types`].
+        #[setters(skip)]
+        map_types: Option<Vec<MapType>>,
+        /// Only keep games that lasted at least this long. The API cannot filter on the
+        /// duration, so like `state` this is a client-side filter. Games without a duration,
+        /// such as ongoing ones, are dropped when either bound is set.
+        min_duration: Option<Duration>,
+        /// Only keep games that lasted at most this long. A client-side filter like
+        /// `min_duration`.
+        max_duration: Option<Duration>,
+        /// Items per page.
+        page_size: Option<usize>,
+        /// Page to start from.
+        page: Option<u32>,
+        /// Number of items to skip.
+        offset: Option<u32>,
+        /// Drop items already returned.
+        dedup: Option<bool>,
+        /// Handling of failed pages.
+        page_failure: Option<PageFailure>,
+        /// Ends the stream at the first game matching this predicate, set with
+        /// [`Self::with_stop_when`].
+        #[cfg_attr(feature = "serde", serde(skip))]
+        #[setters(skip)]
+        stop_when: Option<Filter<Game>>,
+        /// Yield the `stop_when` match.
+        stop_inclusive: Option<bool>,
+        /// Minimum time between page requests.
+        page_delay: Option<Duration>,
+        /// Varying time added to `page_delay`.
+        page_delay_jitter: Option<Duration>,
+        /// [`Client`] used to send requests.
+        #[cfg_attr(feature = "serde", serde(skip))]
+        client: Option<Client>,
+        /// Token to cancel the query.
+        #[cfg_attr(feature = "serde", serde(skip))]
+        cancellation: Option<CancellationToken>,
+    }
+
+    impl Default for ProfileGamesQuery<Missing> {
+        fn default() -> Self {
+            Self {
+                profile_id: Missing,
+                options: ProfileGamesOptions::default(),
+            }
+        }
+    }
+
+    impl<P> ProfileGamesQuery<P> {
+        /// Sets the [`ProfileId`] to query. The query can only be sent once it is set.
+        pub fn with_profile_id(self, profile_id: impl Into<ProfileId>) -> ProfileGamesQuery {
+            ProfileGamesQuery {
+                profile_id: profile_id.into(),
+                options: self.options,
+            }
+        }
+
+        /// Ends the stream at the first game matching `predicate`, e.g. the first one
+        /// started before some date. No further pages are requested once it matches, and
+        /// pages fetched ahead are dropped.
+        pub fn with_stop_when(
+            mut self,
+            predicate: impl Fn(&Game) -> bool + Send + Sync + 'static,
+        ) -> Self {
+            self.options.stop_when = Some(Arc::new(predicate));
+            self
+        }
+
+        /// Only keep games played on one of `maps`. Pages are fetched until `limit` games
+        /// match or there are none left.
+        pub fn with_maps(mut self, maps: impl IntoIterator<Item = Map>) -> Self {
+            self.options.maps = Some(maps.into_iter().collect());
+            self
+        }
+
+        /// Only keep games played on a map of one of `map_types`. Pages are fetched until
+        /// `limit` games match or there are none left.
+        pub fn with_map_types(mut self, map_types: impl IntoIterator<Item = MapType>) -> Self {
+            self.options.map_types = Some(map_types.into_iter().collect());
+            self
+        }
+
+        /// Filters by any of `game_kinds`. Same as `with_game_kind`, but takes any iterator.
+        pub fn with_game_kinds(self, game_kinds: impl IntoIterator<Item = GameKind>) -> Self {
+            self.with_game_kind(Some(game_kinds.into_iter().collect::<Vec<_>>()))
+        }
+
+        /// Filters by any of `leaderboards`. Same as `with_leaderboard`, but takes any
+        /// iterator.
+        pub fn with_leaderboards(
+            self,
+            leaderboards: impl IntoIterator<Item = Leaderboard>,
+        ) -> Self {
+            self.with_leaderboard(Some(leaderboards.into_iter().collect::<Vec<_>>()))
+        }
+
+        /// Filters over a list of opponent profile IDs. Same as `with_opponent_profile_ids`,
+        /// but takes any iterator.
+        pub fn with_opponent_profiles(
+            self,
+            profile_ids: impl IntoIterator<Item = impl Into<ProfileId>>,
+        ) -> Self {
+            self.with_opponent_profile_ids(Some(
+                profile_ids.into_iter().map(Into::into).collect::<Vec<_>>(),
+            ))
+        }
+
+        /// Only keep games still being played, or stop restricting the state if `false`.
+        /// Same as `with_state` with [`GameState::Ongoing`], so it replaces any state set
+        /// before.
+        pub fn only_ongoing(self, only: bool) -> Self {
+            self.with_state(only.then(|| vec![GameState::Ongoing]))
+        }
+
+        /// Only keep games that have ended, whether or not their results are final, or stop
+        /// restricting the state if `false`. Same as `with_state` with
+        /// [`GameState::JustFinished`] and [`GameState::Finished`], so it replaces any state
+        /// set before.
+        pub fn only_finished(self, only: bool) -> Self {
+            self.with_state(only.then(|| vec![GameState::JustFinished, GameState::Finished]))
+        }
     }
 
     impl ProfileGamesQuery {
         /// Get the games for this profile.
+        ///
+        /// Yields at most `limit` items. A `limit` of 0 yields nothing and sends no requests.
+        /// Items are yielded in the API's order: pages are fetched concurrently, but each page is
+        /// only yielded once all those before it have been, however their responses arrive.
         pub async fn get(self, limit: usize) -> Result<impl Stream<Item = Result<Game>>> {
-            if self.profile_id.is_none() {
-                bail!("missing profile_id")
-            }
+            let (client, request) = self.paginate(limit)?;
+            let items = client.into_items(request).await?;
+            Ok(cancellable(items, self.options.cancellation))
+        }
+
+        /// Get the most recent game for this profile matching the query, if any.
+        ///
+        /// Sends a single request for one game, unless a client-side filter such as `state`
+        /// or `maps` is set, in which case pages are fetched until a game matches.
+        pub async fn latest(self) -> Result<Option<Game>> {
+            let mut games = std::pin::pin!(self.get(1).await?);
+            games.next().await.transpose()
+        }
+
+        /// Get every game for this profile.
+        ///
+        /// Unlike [`Self::get`], there is no need to guess a limit up front: pages are
+        /// turned until the last one, however many items that takes.
+        pub async fn get_all(self) -> Result<impl Stream<Item = Result<Game>>> {
+            let (client, request) = self.paginate(usize::MAX)?;
+            let items = client.into_all_items(request).await?;
+            Ok(cancellable(items, self.options.cancellation))
+        }
+
+        /// Get every game, along with a [`CursorHandle`] tracking how far the stream has
+        /// been consumed.
+        ///
+        /// The [`Cursor`] can be saved and passed to [`crate::resume`] to pick up where the
+        /// stream left off, e.g. after a crash.
+        pub async fn get_resumable(
+            self,
+        ) -> Result<(impl Stream<Item = Result<Game>>, CursorHandle)> {
+            let (client, request) = self.paginate(usize::MAX)?;
+            let (items, handle) =
+                client.into_resumable(request, |game: &Game| game.game_id.into(), None)?;
+            Ok((cancellable(items, self.options.cancellation), handle))
+        }
+
+        /// Get the games a page at a time, as they are returned by the server.
+        ///
+        /// Pages hold `page_size` items, except the last one, which may be cut short to
+        /// yield at most `limit` items in total.
+        pub async fn get_pages(
+            self,
+            limit: usize,
+        ) -> Result<impl Stream<Item = Result<Page<Game>>>> {
+            let (client, request) = self.paginate(limit)?;
+            let pages = client.into_page_stream(request).await?;
+            Ok(cancellable(pages, self.options.cancellation))
+        }
+
+        /// Get the games for this profile, along with pagination info such as the total number
+        /// of games.
+        ///
+        /// The first page is fetched before returning, and its items are the first in the
+        /// stream. Yields at most `limit` items.
+        pub async fn get_with_meta(
+            self,
+            limit: usize,
+        ) -> Result<(PaginationMeta, impl Stream<Item = Result<Game>>)> {
+            let (client, request) = self.paginate(limit)?;
+            let (meta, items) = client.into_items_with_meta(request).await?;
+            Ok((meta, cancellable(items, self.options.cancellation)))
+        }
+
+        /// Returns the URL that [`Self::get`] requests, without the `page` and `limit`
+        /// parameters added for each page. Useful to log or report the exact query sent to
+        /// the API.
+        pub fn build_url(&self) -> Result<Url> {
+            self.merged_leaderboards()?;
 
-            let client = PaginationClient::<ProfileGames, Game>::with_limit(limit);
             let url = format!(
                 "https://aoe4world.com/api/v0/players/{}/games",
-                self.profile_id.unwrap()
+                self.profile_id
             )
             .parse()?;
-            let url = self.query_params(url);
+            Ok(self.query_params(url))
+        }
 
-            let pages = client
-                .into_pages_concurrent(PaginatedRequest::new(url))
-                .await?;
-            Ok(pages.items().take(limit))
+        /// Returns the client and first request used to paginate the query.
+        fn paginate(
+            &self,
+            limit: usize,
+        ) -> Result<(PaginationClient<ProfileGames, Game>, PaginatedRequest)> {
+            let url = self.build_url()?;
+            let client = PaginationClient::<ProfileGames, Game>::new(
+                self.options.client.clone().unwrap_or_default(),
+                limit,
+            )
+            .with_page_size(self.options.page_size, MAX_GAMES_COUNT_PER_PAGE)
+            .with_start(self.options.page, self.options.offset)
+            .with_filter(self.filter())
+            .with_dedup(
+                self.options
+                    .dedup
+                    .unwrap_or_default()
+                    .then_some(|game: &Game| game.game_id.into()),
+            )
+            .with_page_failure(self.options.page_failure)
+            .with_stop_when(self.options.stop_when.clone(), self.options.stop_inclusive)
+            .with_page_delay(self.options.page_delay, self.options.page_delay_jitter)
+            .with_reverse(self.options.direction == Some(OrderDirection::Asc));
+            Ok((client, PaginatedRequest::new(url)))
         }
 
-        fn query_params(&self, mut url: Url) -> Url {
-            let mut leaderboards = vec![];
-            if let Some(ref leaderboard) = self.leaderboard {
-                for g in leaderboard.iter().map(|g| g.to_string()) {
-                    leaderboards.push(g)
+        /// Returns the filter for games that the API cannot filter by itself.
+        fn filter(&self) -> Option<Filter<Game>> {
+            all_of([
+                state_filter(self.options.state.clone()),
+                season_filter(self.options.season.clone()),
+                until_filter(self.options.until),
+                maps_filter(self.options.maps.clone()),
+                map_types_filter(self.options.map_types.clone()),
+                duration_filter(self.options.min_duration, self.options.max_duration),
+            ])
+        }
+
+        /// Returns the values sent in the `leaderboard` param, merged from both `leaderboard`
+        /// and `game_kind`, in the order they are sent.
+        ///
+        /// The API filters both leaderboards and game kinds through the same param.
+        /// Leaderboards that correspond to a game kind are sent as that kind, and each value
+        /// is only sent once. Fails if a leaderboard that aggregates game kinds, such as
+        /// [`Leaderboard::RmTeam`], is combined with one of those kinds, such as
+        /// [`GameKind::Rm2v2`]: the API does not handle the overlap.
+        pub fn merged_leaderboards(&self) -> Result<Vec<String>> {
+            let leaderboards = self.leaderboards();
+            for leaderboard in leaderboards.iter().filter_map(|l| l.as_ref().right()) {
+                let kind = leaderboards
+                    .iter()
+                    .filter_map(|k| k.as_ref().left())
+                    .find(|&&kind| leaderboard.aggregates(kind));
+                if let Some(kind) = kind {
+                    bail!(
+                        "leaderboard {leaderboard} overlaps game kind {kind}, filter by only one of them"
+                    );
                 }
             }
-            if let Some(ref game_kind) = self.game_kind {
-                for g in game_kind.iter().map(|g| g.to_string()) {
-                    leaderboards.push(g)
+            Ok(leaderboards.iter().map(ToString::to_string).collect())
+        }
+
+        /// Returns the game kinds and leaderboards to send in the `leaderboard` param,
+        /// without duplicates.
+        fn leaderboards(&self) -> Vec<Either<GameKind, Leaderboard>> {
+            let mut leaderboards = vec![];
+            let kinds = self
+                .options
+                .leaderboard
+                .iter()
+                .flatten()
+                .map(|&l| GameKind::try_from(l).map_or_else(Either::Right, Either::Left))
+                .chain(
+                    self.options
+                        .game_kind
+                        .iter()
+                        .flatten()
+                        .map(|&k| Either::Left(k)),
+                );
+            for kind in kinds {
+                if !leaderboards.contains(&kind) {
+                    leaderboards.push(kind);
                 }
             }
+            leaderboards
+        }
+
+        fn query_params(&self, mut url: Url) -> Url {
+            let leaderboards = self.leaderboards();
             if !leaderboards.is_empty() {
                 url.query_pairs_mut()
                     .append_pair("leaderboard", join(leaderboards, ",").as_str());
             }
-            if let Some(ref id) = self.opponent_profile_id {
+            if let Some(ref id) = self.options.opponent_profile_id {
                 url.query_pairs_mut()
                     .append_pair("opponent_profile_id", id.to_string().as_str());
             }
-            if let Some(ref ids) = self.opponent_profile_ids {
+            if let Some(ref ids) = self.options.opponent_profile_ids {
                 url.query_pairs_mut()
                     .append_pair("opponent_profile_ids", join(ids, ",").as_str());
             }
-            if let Some(ref since) = self.since {
+            if let Some(ref since) = self.options.since {
                 url.query_pairs_mut()
                     .append_pair("since", since.to_rfc3339().as_str());
             }
+            if let Some(ref updated_since) = self.options.updated_since {
+                url.query_pairs_mut()
+                    .append_pair("updated_since", updated_since.to_rfc3339().as_str());
+            }
+            if let Some(ref order) = self.options.order {
+                url.query_pairs_mut()
+                    .append_pair("order", order.to_string().as_str());
+            }
+            if let Some(include_alts) = self.options.include_alts {
+                url.query_pairs_mut()
+                    .append_pair("include_alts", include_alts.to_string().as_str());
+            }
             url
         }
     }
 
     /// Constructs a query for the `/games` endpoint.
+    ///
+    /// Paging works as described under [paging options](crate::Page#paging-options).
     #[derive(Setters, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[setters(prefix = "with_")]
     #[setters(into)]
     pub struct GlobalGamesQuery {
@@ -243,30 +804,320 @@ pub mod query {
         leaderboard: Option<Vec<GameKind>>,
         /// Filter over an opponent's profile ID.
         opponent_profile_id: Option<ProfileId>,
+        /// Filter over a list of opponent profile IDs, i.e. games involving any of them.
+        /// Cannot be combined with `opponent_profile_id`.
+        opponent_profile_ids: Option<Vec<ProfileId>>,
         /// Filter over a list of profile IDs.
         profile_ids: Option<Vec<ProfileId>>,
         /// Filter by time played since a specific date.
         since: Option<chrono::DateTime<chrono::Utc>>,
-        /// Filter by time played since a specific date.
+        /// Only keep games started before a specific date. Together with `since`, this
+        /// bounds a window of games. The API cannot filter on it, so this is a client-side
+        /// filter: games are newest first, so those started later are fetched and dropped
+        /// before the first matching one. Games without a start date are dropped.
+        until: Option<chrono::DateTime<chrono::Utc>>,
+        /// Order of the games, most recent first.
         order: Option<GamesOrder>,
+        /// Direction of the order. Defaults to [`OrderDirection::Desc`], most recent first.
+        /// The API cannot order games oldest first, so [`OrderDirection::Asc`] fetches pages
+        /// from the last one back, and fails if the API does not report the total number of
+        /// games. Filters and `stop_when` apply oldest first. It is only supported by `get`
+        /// and `get_all`; other methods return an error.
+        direction: Option<OrderDirection>,
+        /// Filter by [`Platform`]. Expands into the matching set of [`GameKind`]s, further
+        /// restricting `leaderboard` if it is also set.
+        platform: Option<Platform>,
+        /// Only keep games whose `average_rating` is at least this. The API cannot filter on
+        /// the rating, so like `state` this is a client-side filter. Games without an
+        /// average rating are dropped when either bound is set.
+        min_average_rating: Option<f64>,
+        /// Only keep games whose `average_rating` is at most this. A client-side filter like
+        /// `min_average_rating`.
+        max_average_rating: Option<f64>,
+        /// Only keep games in one of these [`GameState`]s. The API cannot filter on the
+        /// state, so this is a client-side filter: other games are dropped from the stream.
+        /// The limit then counts matching games, while `page` and `offset` still count every
+        /// game.
+        state: Option<Vec<GameState>>,
+        /// Only keep games played in one of these seasons. The API cannot filter on the
+        /// season, so like `state` this is a client-side filter. Games without a season
+        /// are dropped.
+        season: Option<Vec<u32>>,
+        /// Only keep games played on one of these [`Map`]s. The API cannot filter on the
+        /// map, so like `state` this is a client-side filter. Set with [`Self::with_maps`].
+        #[setters(skip)]
+        maps: Option<Vec<Map>>,
+        /// Only keep games played on a map of one of these [`MapType`]s, e.g. water maps.
+        /// A client-side filter like `maps`. Set with [`Self::with_map_types`].
+        #[setters(skip)]
+        map_types: Option<Vec<MapType>>,
+        /// Only keep games that lasted at least this long. The API cannot filter on the
+        /// duration, so like `state` this is a client-side filter. Games without a duration,
+        /// such as ongoing ones, are dropped when either bound is set.
+        min_duration: Option<Duration>,
+        /// Only keep games that lasted at most this long. A client-side filter like
+        /// `min_duration`.
+        max_duration: Option<Duration>,
+        /// Items per page.
+        page_size: Option<usize>,
+        /// Page to start from.
+        page: Option<u32>,
+        /// Number of items to skip.
+        offset: Option<u32>,
+        /// Drop items already returned.
+        dedup: Option<bool>,
+        /// Handling of failed pages.
+        page_failure: Option<PageFailure>,
+        /// Ends the stream at the first game matching this predicate, set with
+        /// [`Self::with_stop_when`].
+        #[cfg_attr(feature = "serde", serde(skip))]
+        #[setters(skip)]
+        stop_when: Option<Filter<Game>>,
+        /// Yield the `stop_when` match.
+        stop_inclusive: Option<bool>,
+        /// Minimum time between page requests.
+        page_delay: Option<Duration>,
+        /// Varying time added to `page_delay`.
+        page_delay_jitter: Option<Duration>,
+        /// [`Client`] used to send requests.
+        #[cfg_attr(feature = "serde", serde(skip))]
+        client: Option<Client>,
+        /// Token to cancel the query.
+        #[cfg_attr(feature = "serde", serde(skip))]
+        cancellation: Option<CancellationToken>,
     }
 
     impl GlobalGamesQuery {
+        /// Ends the stream at the first game matching `predicate`, e.g. the first one
+        /// started before some date. No further pages are requested once it matches, and
+        /// pages fetched ahead are dropped.
+        pub fn with_stop_when(
+            mut self,
+            predicate: impl Fn(&Game) -> bool + Send + Sync + 'static,
+        ) -> Self {
+            self.stop_when = Some(Arc::new(predicate));
+            self
+        }
+
+        /// Only keep games played on one of `maps`. Pages are fetched until `limit` games
+        /// match or there are none left.
+        pub fn with_maps(mut self, maps: impl IntoIterator<Item = Map>) -> Self {
+            self.maps = Some(maps.into_iter().collect());
+            self
+        }
+
+        /// Only keep games played on a map of one of `map_types`. Pages are fetched until
+        /// `limit` games match or there are none left.
+        pub fn with_map_types(mut self, map_types: impl IntoIterator<Item = MapType>) -> Self {
+            self.map_types = Some(map_types.into_iter().collect());
+            self
+        }
+
+        /// Only keep games played on a map of `map_type`, or stop restricting the map type
+        /// if `None`. Same as [`Self::with_map_types`] with a single type.
+        pub fn with_map_type(mut self, map_type: Option<MapType>) -> Self {
+            match map_type {
+                Some(map_type) => self.with_map_types([map_type]),
+                None => {
+                    self.map_types = None;
+                    self
+                }
+            }
+        }
+
+        /// Filters by any of `game_kinds`. Same as `with_leaderboard`, which takes
+        /// [`GameKind`]s on this endpoint, but takes any iterator.
+        pub fn with_game_kinds(self, game_kinds: impl IntoIterator<Item = GameKind>) -> Self {
+            self.with_leaderboard(Some(game_kinds.into_iter().collect::<Vec<_>>()))
+        }
+
+        /// Filters over a list of profile IDs. Same as `with_profile_ids`, but takes any
+        /// iterator.
+        pub fn with_profiles(
+            self,
+            profile_ids: impl IntoIterator<Item = impl Into<ProfileId>>,
+        ) -> Self {
+            self.with_profile_ids(Some(
+                profile_ids.into_iter().map(Into::into).collect::<Vec<_>>(),
+            ))
+        }
+
+        /// Filters over a list of opponent profile IDs. Same as `with_opponent_profile_ids`,
+        /// but takes any iterator.
+        pub fn with_opponent_profiles(
+            self,
+            profile_ids: impl IntoIterator<Item = impl Into<ProfileId>>,
+        ) -> Self {
+            self.with_opponent_profile_ids(Some(
+                profile_ids.into_iter().map(Into::into).collect::<Vec<_>>(),
+            ))
+        }
+
+        /// Only keep games still being played, or stop restricting the state if `false`.
+        /// Same as `with_state` with [`GameState::Ongoing`], so it replaces any state set
+        /// before.
+        pub fn only_ongoing(self, only: bool) -> Self {
+            self.with_state(only.then(|| vec![GameState::Ongoing]))
+        }
+
+        /// Only keep games that have ended, whether or not their results are final, or stop
+        /// restricting the state if `false`. Same as `with_state` with
+        /// [`GameState::JustFinished`] and [`GameState::Finished`], so it replaces any state
+        /// set before.
+        pub fn only_finished(self, only: bool) -> Self {
+            self.with_state(only.then(|| vec![GameState::JustFinished, GameState::Finished]))
+        }
+
         /// Get the games.
+        ///
+        /// Yields at most `limit` items. A `limit` of 0 yields nothing and sends no requests.
+        /// Items are yielded in the API's order: pages are fetched concurrently, but each page is
+        /// only yielded once all those before it have been, however their responses arrive.
         pub async fn get(self, limit: usize) -> Result<impl Stream<Item = Result<Game>>> {
-            let client = PaginationClient::<GlobalGames, Game>::with_limit(limit);
+            let (client, request) = self.paginate(limit)?;
+            let items = client.into_items(request).await?;
+            Ok(cancellable(items, self.cancellation))
+        }
+
+        /// Get every game.
+        ///
+        /// Unlike [`Self::get`], there is no need to guess a limit up front: pages are
+        /// turned until the last one, however many items that takes.
+        pub async fn get_all(self) -> Result<impl Stream<Item = Result<Game>>> {
+            let (client, request) = self.paginate(usize::MAX)?;
+            let items = client.into_all_items(request).await?;
+            Ok(cancellable(items, self.cancellation))
+        }
+
+        /// Get every game, along with a [`CursorHandle`] tracking how far the stream has
+        /// been consumed.
+        ///
+        /// The [`Cursor`] can be saved and passed to [`crate::resume`] to pick up where the
+        /// stream left off, e.g. after a crash.
+        pub async fn get_resumable(
+            self,
+        ) -> Result<(impl Stream<Item = Result<Game>>, CursorHandle)> {
+            let (client, request) = self.paginate(usize::MAX)?;
+            let (items, handle) =
+                client.into_resumable(request, |game: &Game| game.game_id.into(), None)?;
+            Ok((cancellable(items, self.cancellation), handle))
+        }
+
+        /// Get the games a page at a time, as they are returned by the server.
+        ///
+        /// Pages hold `page_size` items, except the last one, which may be cut short to
+        /// yield at most `limit` items in total.
+        ///
+        /// When filtering by `map_types`, games on other map types are dropped from their
+        /// page, and pages are fetched one at a time until enough games match.
+        pub async fn get_pages(
+            self,
+            limit: usize,
+        ) -> Result<impl Stream<Item = Result<Page<Game>>>> {
+            let (client, request) = self.paginate(limit)?;
+            let pages = client.into_page_stream(request).await?;
+            Ok(cancellable(pages, self.cancellation))
+        }
+
+        /// Get the games, along with pagination info such as the total number of games.
+        ///
+        /// The first page is fetched before returning, and its items are the first in the
+        /// stream. Yields at most `limit` items.
+        pub async fn get_with_meta(
+            self,
+            limit: usize,
+        ) -> Result<(PaginationMeta, impl Stream<Item = Result<Game>>)> {
+            let (client, request) = self.paginate(limit)?;
+            let (meta, items) = client.into_items_with_meta(request).await?;
+            Ok((meta, cancellable(items, self.cancellation)))
+        }
+
+        /// Returns the URL that [`Self::get`] requests, without the `page` and `limit`
+        /// parameters added for each page. Useful to log or report the exact query sent to
+        /// the API.
+        pub fn build_url(&self) -> Result<Url> {
+            if matches!(self.leaderboards(), Some(ref l) if l.is_empty()) {
+                bail!("leaderboard filter excludes every game kind on the requested platform");
+            }
+            if self.opponent_profile_id.is_some() && self.opponent_profile_ids.is_some() {
+                bail!("opponent_profile_id and opponent_profile_ids cannot both be set");
+            }
 
             let url = "https://aoe4world.com/api/v0/games".parse()?;
-            let url = self.query_params(url);
+            Ok(self.query_params(url))
+        }
 
-            let pages = client
-                .into_pages_concurrent(PaginatedRequest::new(url))
-                .await?;
-            Ok(pages.items().take(limit))
+        /// Returns the client and first request used to paginate the query.
+        fn paginate(
+            &self,
+            limit: usize,
+        ) -> Result<(PaginationClient<GlobalGames, Game>, PaginatedRequest)> {
+            let url = self.build_url()?;
+            let client = PaginationClient::<GlobalGames, Game>::new(
+                self.client.clone().unwrap_or_default(),
+                limit,
+            )
+            .with_page_size(self.page_size, MAX_GAMES_COUNT_PER_PAGE)
+            .with_start(self.page, self.offset)
+            .with_filter(self.filter())
+            .with_dedup(
+                self.dedup
+                    .unwrap_or_default()
+                    .then_some(|game: &Game| game.game_id.into()),
+            )
+            .with_page_failure(self.page_failure)
+            .with_stop_when(self.stop_when.clone(), self.stop_inclusive)
+            .with_page_delay(self.page_delay, self.page_delay_jitter)
+            .with_reverse(self.direction == Some(OrderDirection::Asc));
+            Ok((client, PaginatedRequest::new(url)))
+        }
+
+        /// Returns the filter for games that the API cannot filter by itself.
+        fn filter(&self) -> Option<Filter<Game>> {
+            all_of([
+                self.average_rating_filter(),
+                state_filter(self.state.clone()),
+                season_filter(self.season.clone()),
+                until_filter(self.until),
+                maps_filter(self.maps.clone()),
+                map_types_filter(self.map_types.clone()),
+                duration_filter(self.min_duration, self.max_duration),
+            ])
+        }
+
+        /// Returns the filter for games whose average rating lies between
+        /// `min_average_rating` and `max_average_rating`, inclusive.
+        fn average_rating_filter(&self) -> Option<Filter<Game>> {
+            if self.min_average_rating.is_none() && self.max_average_rating.is_none() {
+                return None;
+            }
+            let min = self.min_average_rating.unwrap_or(f64::NEG_INFINITY);
+            let max = self.max_average_rating.unwrap_or(f64::INFINITY);
+            Some(Arc::new(move |game: &Game| {
+                game.average_rating
+                    .is_some_and(|rating| (min..=max).contains(&rating))
+            }))
+        }
+
+        /// Returns the game kinds to filter by, taking into account both `leaderboard` and
+        /// `platform`.
+        fn leaderboards(&self) -> Option<Vec<GameKind>> {
+            let platform = self.platform.unwrap_or_default();
+            match self.leaderboard {
+                Some(ref leaderboard) => Some(
+                    leaderboard
+                        .iter()
+                        .filter(|kind| platform.includes(kind))
+                        .copied()
+                        .collect(),
+                ),
+                None if platform == Platform::All => None,
+                None => Some(platform.game_kinds()),
+            }
         }
 
         fn query_params(&self, mut url: Url) -> Url {
-            if let Some(ref leaderboard) = self.leaderboard {
+            if let Some(leaderboard) = self.leaderboards() {
                 url.query_pairs_mut()
                     .append_pair("leaderboard", join(leaderboard, ",").as_str());
             }
@@ -274,6 +1125,10 @@ pub mod query {
                 url.query_pairs_mut()
                     .append_pair("opponent_profile_id", id.to_string().as_str());
             }
+            if let Some(ref ids) = self.opponent_profile_ids {
+                url.query_pairs_mut()
+                    .append_pair("opponent_profile_ids", join(ids, ",").as_str());
+            }
             if let Some(ref ids) = self.profile_ids {
                 url.query_pairs_mut()
                     .append_pair("profile_ids", join(ids, ",").as_str());
@@ -290,74 +1145,472 @@ pub mod query {
         }
     }
 
-    /// Constructs a query for the `/players/{profile_id}` endpoint.
+    /// Returns the filter for games in one of `states`.
+    fn state_filter(states: Option<Vec<GameState>>) -> Option<Filter<Game>> {
+        let states = states?;
+        Some(Arc::new(move |game: &Game| states.contains(&game.state())))
+    }
+
+    /// Returns the filter for games started before `until`.
+    fn until_filter(until: Option<chrono::DateTime<chrono::Utc>>) -> Option<Filter<Game>> {
+        let until = until?;
+        Some(Arc::new(move |game: &Game| {
+            game.started_at.is_some_and(|started_at| started_at < until)
+        }))
+    }
+
+    /// Returns the filter for games that lasted between `min` and `max`, inclusive.
+    fn duration_filter(min: Option<Duration>, max: Option<Duration>) -> Option<Filter<Game>> {
+        if min.is_none() && max.is_none() {
+            return None;
+        }
+        let min = min.unwrap_or(Duration::ZERO);
+        let max = max.unwrap_or(Duration::MAX);
+        Some(Arc::new(move |game: &Game| {
+            game.duration
+                .is_some_and(|secs| (min..=max).contains(&Duration::from_secs(secs.into())))
+        }))
+    }
+
+    /// Returns the filter for games played on one of `maps`.
+    fn maps_filter(maps: Option<Vec<Map>>) -> Option<Filter<Game>> {
+        let maps = maps?;
+        Some(Arc::new(move |game: &Game| {
+            game.map.as_ref().is_some_and(|map| maps.contains(map))
+        }))
+    }
+
+    /// Returns the filter for games played on a map of one of `map_types`.
+    fn map_types_filter(map_types: Option<Vec<MapType>>) -> Option<Filter<Game>> {
+        let map_types = map_types?;
+        Some(Arc::new(move |game: &Game| {
+            game.map
+                .as_ref()
+                .is_some_and(|map| map_types.contains(&map.map_type()))
+        }))
+    }
+
+    /// Returns the filter for games played in one of `seasons`.
+    fn season_filter(seasons: Option<Vec<u32>>) -> Option<Filter<Game>> {
+        let seasons = seasons?;
+        Some(Arc::new(move |game: &Game| {
+            game.season.is_some_and(|season| seasons.contains(&season))
+        }))
+    }
+
+    /// Constructs a query for the `/players/{profile_id}/games/{game_id}` endpoint.
     #[derive(Setters, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[setters(prefix = "with_")]
     #[setters(into)]
-    pub struct ProfileQuery {
-        /// [`ProfileId`] to query.
+    pub struct GameQuery {
+        /// [`ProfileId`] of a player in the game.
         profile_id: Option<ProfileId>,
+        /// ID of the game.
+        game_id: Option<u32>,
+        /// [`Client`] used to send requests.
+        #[cfg_attr(feature = "serde", serde(skip))]
+        client: Option<Client>,
     }
 
-    impl ProfileQuery {
+    impl GameQuery {
+        /// Get the game.
+        ///
+        /// Returns [`crate::Error::NotFound`] if the player has no game with this ID.
+        pub async fn get(self) -> Result<Game> {
+            let url = self.build_url()?;
+            self.client.unwrap_or_default().get_json(url).await
+        }
+
+        /// Returns the URL that [`Self::get`] requests.
+        pub fn build_url(&self) -> Result<Url> {
+            let Some(profile_id) = self.profile_id else {
+                bail!("missing profile_id")
+            };
+            let Some(game_id) = self.game_id else {
+                bail!("missing game_id")
+            };
+            let url = format!("https://aoe4world.com/api/v0/players/{profile_id}/games/{game_id}")
+                .parse()?;
+            Ok(url)
+        }
+
+        /// Get the game, or `None` if the player has no game with this ID.
+        pub async fn get_optional(self) -> Result<Option<Game>> {
+            optional(self.get().await)
+        }
+    }
+
+    /// Constructs a query for the `/players/{profile_id}` endpoint.
+    #[derive(Setters, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[setters(prefix = "with_")]
+    #[setters(into)]
+    pub struct ProfileQuery {
+        /// [`ProfileId`] to query.
+        profile_id: Option<ProfileId>,
+        /// [`Client`] used to send requests.
+        #[cfg_attr(feature = "serde", serde(skip))]
+        client: Option<Client>,
+    }
+
+    impl ProfileQuery {
         /// Get the profile.
+        ///
+        /// Returns [`crate::Error::NotFound`] if there is no player with this profile ID.
         pub async fn get(self) -> Result<Profile> {
+            let url = self.build_url()?;
+            self.client.unwrap_or_default().get_json(url).await
+        }
+
+        /// Returns the URL that [`Self::get`] requests.
+        pub fn build_url(&self) -> Result<Url> {
             if self.profile_id.is_none() {
                 bail!("missing profile_id")
             }
 
-            reqwest::get(format!(
+            let url = format!(
                 "https://aoe4world.com/api/v0/players/{}",
                 self.profile_id.unwrap()
-            ))
-            .await?
-            .json()
-            .await
-            .map_err(anyhow::Error::from)
+            )
+            .parse()?;
+            Ok(url)
+        }
+
+        /// Get the profile, or `None` if there is no player with this profile ID.
+        pub async fn get_optional(self) -> Result<Option<Profile>> {
+            optional(self.get().await)
+        }
+    }
+
+    /// Constructs a query resuming a [`ProfileGamesQuery`] or [`GlobalGamesQuery`] from a
+    /// [`Cursor`].
+    ///
+    /// Filters sent to the API are part of the cursor. Those applied to the stream, such as
+    /// `map_types`, `dedup` or `stop_when`, are not carried over.
+    ///
+    /// Paging works as described under [paging options](crate::Page#paging-options).
+    #[derive(Setters, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[setters(prefix = "with_")]
+    #[setters(into)]
+    pub struct ResumeQuery {
+        /// [`Cursor`] to resume from.
+        cursor: Option<Cursor>,
+        /// Handling of failed pages.
+        page_failure: Option<PageFailure>,
+        /// Minimum time between page requests.
+        page_delay: Option<Duration>,
+        /// Varying time added to `page_delay`.
+        page_delay_jitter: Option<Duration>,
+        /// [`Client`] used to send requests.
+        #[cfg_attr(feature = "serde", serde(skip))]
+        client: Option<Client>,
+        /// Token to cancel the query.
+        #[cfg_attr(feature = "serde", serde(skip))]
+        cancellation: Option<CancellationToken>,
+    }
+
+    impl ResumeQuery {
+        /// Get the rest of the games, starting after the last one returned before the
+        /// cursor was saved, along with a [`CursorHandle`] to resume again.
+        pub async fn get_resumable(
+            self,
+        ) -> Result<(impl Stream<Item = Result<Game>>, CursorHandle)> {
+            let url = self.build_url()?;
+            let cursor = self.cursor.expect("cursor should be checked by build_url");
+
+            // Both games endpoints return pages of the same shape.
+            let client = PaginationClient::<GlobalGames, Game>::new(
+                self.client.unwrap_or_default(),
+                usize::MAX,
+            )
+            .with_page_size(Some(cursor.per_page), MAX_GAMES_COUNT_PER_PAGE)
+            .with_start(Some(cursor.page), None)
+            .with_page_failure(self.page_failure)
+            .with_page_delay(self.page_delay, self.page_delay_jitter);
+            let (items, handle) = client.into_resumable(
+                PaginatedRequest::new(url),
+                |game: &Game| game.game_id.into(),
+                cursor.last_key,
+            )?;
+            Ok((cancellable(items, self.cancellation), handle))
+        }
+
+        /// Returns the URL that [`Self::get_resumable`] requests, without the `page` and
+        /// `limit` parameters added for each page.
+        pub fn build_url(&self) -> Result<Url> {
+            let Some(cursor) = &self.cursor else {
+                bail!("missing cursor")
+            };
+            if ![Endpoint::Games, Endpoint::ProfileGames]
+                .iter()
+                .any(|endpoint| endpoint.matches(&cursor.url))
+            {
+                bail!("cursor is not for a games query: {}", cursor.url)
+            }
+            Ok(cursor.url.clone())
+        }
+    }
+
+    /// Default number of profiles to fetch concurrently in a [`ProfilesQuery`].
+    const DEFAULT_PROFILES_CONCURRENCY: usize = 8;
+
+    /// Constructs concurrent queries for the `/players/{profile_id}` endpoint.
+    #[derive(Setters, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[setters(prefix = "with_")]
+    #[setters(into)]
+    pub struct ProfilesQuery {
+        /// [`ProfileId`]s to query.
+        profile_ids: Option<Vec<ProfileId>>,
+        /// [`Client`] used to send requests.
+        #[cfg_attr(feature = "serde", serde(skip))]
+        client: Option<Client>,
+    }
+
+    impl ProfilesQuery {
+        /// Get the profiles. Returns a stream of [`Profile`] in the same order as the
+        /// requested profile IDs.
+        ///
+        /// Profiles are fetched concurrently. Each item is its own [`Result`], so failing
+        /// to fetch one profile does not prevent the others from being returned.
+        pub async fn get(self) -> Result<impl Stream<Item = Result<Profile>>> {
+            if self.profile_ids.is_none() {
+                bail!("missing profile_ids")
+            }
+
+            let client = self.client.unwrap_or_default();
+            Ok(futures::stream::iter(self.profile_ids.unwrap())
+                .map(move |id| {
+                    ProfileQuery::default()
+                        .with_profile_id(Some(id))
+                        .with_client(Some(client.clone()))
+                        .get()
+                })
+                .buffered(DEFAULT_PROFILES_CONCURRENCY))
+        }
+
+        /// Returns the URLs that [`Self::get`] requests, one per profile, in order.
+        pub fn build_urls(&self) -> Result<Vec<Url>> {
+            let Some(ref profile_ids) = self.profile_ids else {
+                bail!("missing profile_ids")
+            };
+            profile_ids
+                .iter()
+                .map(|&id| {
+                    ProfileQuery::default()
+                        .with_profile_id(Some(id))
+                        .build_url()
+                })
+                .collect()
         }
     }
 
     /// Constructs a query for the `/players/search` endpoint.
+    ///
+    /// Paging works as described under [paging options](crate::Page#paging-options).
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct SearchQuery<Q = String> {
+        /// Search query. Required, set with [`Self::with_query`].
+        query: Q,
+        /// Every other parameter, see [`Missing`].
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        options: SearchOptions,
+    }
+
+    /// Options of a [`SearchQuery`] other than its required search query.
     #[derive(Setters, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[setters(prefix = "with_")]
     #[setters(into)]
-    pub struct SearchQuery {
-        /// Search query.
-        query: Option<String>,
+    #[setters(generate_delegates(ty = "SearchQuery<Q>", generics = "<Q>", field = "options"))]
+    struct SearchOptions {
         /// Should the results exactly match the query.
         exact: Option<bool>,
+        /// Only keep players with stats on this [`Leaderboard`]. This is a client-side
+        /// filter over the profiles in each page of results, so pages are fetched one at a
+        /// time until `limit` players match.
+        leaderboard: Option<Leaderboard>,
+        /// Only keep players rated at least this much, on `leaderboard` if it is set or on
+        /// any mode otherwise. Like `leaderboard`, this is a client-side filter.
+        min_rating: Option<i64>,
+        /// Items per page.
+        page_size: Option<usize>,
+        /// Page to start from.
+        page: Option<u32>,
+        /// Number of items to skip.
+        offset: Option<u32>,
+        /// Drop items already returned.
+        dedup: Option<bool>,
+        /// Handling of failed pages.
+        page_failure: Option<PageFailure>,
+        /// Ends the stream at the first profile matching this predicate, set with
+        /// [`Self::with_stop_when`].
+        #[cfg_attr(feature = "serde", serde(skip))]
+        #[setters(skip)]
+        stop_when: Option<Filter<Profile>>,
+        /// Yield the `stop_when` match.
+        stop_inclusive: Option<bool>,
+        /// Minimum time between page requests.
+        page_delay: Option<Duration>,
+        /// Varying time added to `page_delay`.
+        page_delay_jitter: Option<Duration>,
+        /// [`Client`] used to send requests.
+        #[cfg_attr(feature = "serde", serde(skip))]
+        client: Option<Client>,
+        /// Token to cancel the query.
+        #[cfg_attr(feature = "serde", serde(skip))]
+        cancellation: Option<CancellationToken>,
+    }
+
+    impl Default for SearchQuery<Missing> {
+        fn default() -> Self {
+            Self {
+                query: Missing,
+                options: SearchOptions::default(),
+            }
+        }
+    }
+
+    impl<Q> SearchQuery<Q> {
+        /// Sets the search query. The query can only be sent once it is set.
+        pub fn with_query(self, query: impl Into<String>) -> SearchQuery {
+            SearchQuery {
+                query: query.into(),
+                options: self.options,
+            }
+        }
+
+        /// Ends the stream at the first profile matching `predicate`. No further
+        /// pages are requested once it matches, and pages fetched ahead are dropped.
+        pub fn with_stop_when(
+            mut self,
+            predicate: impl Fn(&Profile) -> bool + Send + Sync + 'static,
+        ) -> Self {
+            self.options.stop_when = Some(Arc::new(predicate));
+            self
+        }
     }
 
     impl SearchQuery {
         /// Get the search results.
+        ///
+        /// Yields at most `limit` items. A `limit` of 0 yields nothing and sends no requests.
+        /// Items are yielded in the API's order: pages are fetched concurrently, but each page is
+        /// only yielded once all those before it have been, however their responses arrive.
         pub async fn get(self, limit: usize) -> Result<impl Stream<Item = Result<Profile>>> {
-            if self.query.is_none() {
-                bail!("missing search query");
-            }
-            if self.query.as_ref().unwrap().len() < 3 {
-                bail!(
-                    "search query must contain at least 3 characters, got {}",
-                    self.query.as_ref().unwrap().len()
-                );
-            }
+            let (client, request) = self.paginate(limit)?;
+            let items = client.into_items(request).await?;
+            Ok(cancellable(items, self.options.cancellation))
+        }
+
+        /// Get the first matching profile, or `None` if no player matches.
+        pub async fn first(self) -> Result<Option<Profile>> {
+            let profiles = self.get(1).await?;
+            std::pin::pin!(profiles).try_next().await
+        }
+
+        /// Get every search result.
+        ///
+        /// Unlike [`Self::get`], there is no need to guess a limit up front: pages are
+        /// turned until the last one, however many items that takes.
+        pub async fn get_all(self) -> Result<impl Stream<Item = Result<Profile>>> {
+            let (client, request) = self.paginate(usize::MAX)?;
+            let items = client.into_all_items(request).await?;
+            Ok(cancellable(items, self.options.cancellation))
+        }
+
+        /// Get the search results a page at a time, as they are returned by the server.
+        ///
+        /// Pages hold `page_size` items, except the last one, which may be cut short to
+        /// yield at most `limit` items in total.
+        pub async fn get_pages(
+            self,
+            limit: usize,
+        ) -> Result<impl Stream<Item = Result<Page<Profile>>>> {
+            let (client, request) = self.paginate(limit)?;
+            let pages = client.into_page_stream(request).await?;
+            Ok(cancellable(pages, self.options.cancellation))
+        }
+
+        /// Get the search results, along with pagination info such as the total number of
+        /// results.
+        ///
+        /// The first page is fetched before returning, and its items are the first in the
+        /// stream. Yields at most `limit` items.
+        pub async fn get_with_meta(
+            self,
+            limit: usize,
+        ) -> Result<(PaginationMeta, impl Stream<Item = Result<Profile>>)> {
+            let (client, request) = self.paginate(limit)?;
+            let (meta, items) = client.into_items_with_meta(request).await?;
+            Ok((meta, cancellable(items, self.options.cancellation)))
+        }
 
-            let client = PaginationClient::<SearchResults, Profile>::with_limit(limit);
+        /// Returns the URL that [`Self::get`] requests, without the `page` and `limit`
+        /// parameters added for each page. Useful to log or report the exact query sent to
+        /// the API.
+        pub fn build_url(&self) -> Result<Url> {
+            let chars = self.query.chars().count();
+            if chars < 3 {
+                bail!("search query must contain at least 3 characters, got {chars}");
+            }
 
             let url = "https://aoe4world.com/api/v0/players/search".parse()?;
-            let url = self.query_params(url);
+            Ok(self.query_params(url))
+        }
 
-            let pages = client
-                .into_pages_concurrent(PaginatedRequest::new(url))
-                .await?;
-            Ok(pages.items().take(limit))
+        /// Returns the client and first request used to paginate the query.
+        fn paginate(
+            &self,
+            limit: usize,
+        ) -> Result<(PaginationClient<SearchResults, Profile>, PaginatedRequest)> {
+            let url = self.build_url()?;
+            let client = PaginationClient::<SearchResults, Profile>::new(
+                self.options.client.clone().unwrap_or_default(),
+                limit,
+            )
+            .with_page_size(self.options.page_size, MAX_COUNT_PER_PAGE)
+            .with_start(self.options.page, self.options.offset)
+            .with_filter(self.filter())
+            .with_dedup(
+                self.options
+                    .dedup
+                    .unwrap_or_default()
+                    .then_some(|profile: &Profile| profile.profile_id.into()),
+            )
+            .with_page_failure(self.options.page_failure)
+            .with_stop_when(self.options.stop_when.clone(), self.options.stop_inclusive)
+            .with_page_delay(self.options.page_delay, self.options.page_delay_jitter);
+            Ok((client, PaginatedRequest::new(url)))
         }
 
-        fn query_params(&self, mut url: Url) -> Url {
-            if let Some(query) = &self.query {
-                url.query_pairs_mut()
-                    .append_pair("query", query.to_string().as_str());
+        /// Returns the client-side filter for `leaderboard` and `min_rating`, if any.
+        fn filter(&self) -> Option<Filter<Profile>> {
+            if self.options.leaderboard.is_none() && self.options.min_rating.is_none() {
+                return None;
             }
-            if let Some(exact) = self.exact {
+            let leaderboard = self.options.leaderboard;
+            let min_rating = self.options.min_rating;
+            Some(Arc::new(move |profile: &Profile| {
+                let Some(modes) = profile.modes.as_ref() else {
+                    return false;
+                };
+                let rated = |stats: &GameModeStats| {
+                    min_rating.is_none_or(|min| stats.rating.is_some_and(|r| r >= min))
+                };
+                match leaderboard {
+                    Some(leaderboard) => modes.get(leaderboard).is_some_and(rated),
+                    None => modes.iter().any(|(_, stats)| rated(stats)),
+                }
+            }))
+        }
+
+        fn query_params(&self, mut url: Url) -> Url {
+            url.query_pairs_mut()
+                .append_pair("query", self.query.as_str());
+            if let Some(exact) = self.options.exact {
                 url.query_pairs_mut()
                     .append_pair("exact", exact.to_string().as_str());
             }
@@ -366,58 +1619,280 @@ pub mod query {
     }
 
     /// Constructs a query for the `/leaderboards/leaderboard` endpoint.
+    ///
+    /// Paging works as described under [paging options](crate::Page#paging-options).
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct LeaderboardQuery<L = Leaderboard> {
+        /// [`Leaderboard`] to query. Required, set with [`Self::with_leaderboard`].
+        leaderboard: L,
+        /// Every other parameter, see [`Missing`].
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        options: LeaderboardOptions,
+    }
+
+    /// Options of a [`LeaderboardQuery`] other than its required [`Leaderboard`].
     #[derive(Setters, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[setters(prefix = "with_")]
     #[setters(into)]
-    pub struct LeaderboardQuery {
-        /// [`ProfileId`] to query.
-        leaderboard: Option<Leaderboard>,
+    #[setters(generate_delegates(ty = "LeaderboardQuery<L>", generics = "<L>", field = "options"))]
+    struct LeaderboardOptions {
         /// [`ProfileId`] to query.
         profile_id: Option<ProfileId>,
         /// Search query.
         query: Option<String>,
         /// Search by country.
         country: Option<CountryCode>,
+        /// Season to get the final standings of, e.g. 7. Defaults to the current season.
+        /// Standings of past seasons no longer change, so the disk cache keeps them by
+        /// default.
+        season: Option<u32>,
+        /// Items per page.
+        page_size: Option<usize>,
+        /// Page to start from.
+        page: Option<u32>,
+        /// Number of items to skip.
+        offset: Option<u32>,
+        /// Ranks to return, counting from 1, set with [`Self::with_rank_range`]. Cannot be
+        /// combined with `page` or `offset`.
+        #[setters(skip)]
+        rank_range: Option<Range<u32>>,
+        /// Drop items already returned.
+        dedup: Option<bool>,
+        /// Handling of failed pages.
+        page_failure: Option<PageFailure>,
+        /// Ends the stream at the first entry matching this predicate, set with
+        /// [`Self::with_stop_when`].
+        #[cfg_attr(feature = "serde", serde(skip))]
+        #[setters(skip)]
+        stop_when: Option<Filter<LeaderboardEntry>>,
+        /// Yield the `stop_when` match.
+        stop_inclusive: Option<bool>,
+        /// Minimum time between page requests.
+        page_delay: Option<Duration>,
+        /// Varying time added to `page_delay`.
+        page_delay_jitter: Option<Duration>,
+        /// [`Client`] used to send requests.
+        #[cfg_attr(feature = "serde", serde(skip))]
+        client: Option<Client>,
+        /// Token to cancel the query.
+        #[cfg_attr(feature = "serde", serde(skip))]
+        cancellation: Option<CancellationToken>,
+    }
+
+    impl Default for LeaderboardQuery<Missing> {
+        fn default() -> Self {
+            Self {
+                leaderboard: Missing,
+                options: LeaderboardOptions::default(),
+            }
+        }
+    }
+
+    impl<L> LeaderboardQuery<L> {
+        /// Sets the [`Leaderboard`] to query. The query can only be sent once it is set.
+        pub fn with_leaderboard(self, leaderboard: impl Into<Leaderboard>) -> LeaderboardQuery {
+            LeaderboardQuery {
+                leaderboard: leaderboard.into(),
+                options: self.options,
+            }
+        }
+
+        /// Ends the stream at the first entry matching `predicate`. No further
+        /// pages are requested once it matches, and pages fetched ahead are dropped.
+        pub fn with_stop_when(
+            mut self,
+            predicate: impl Fn(&LeaderboardEntry) -> bool + Send + Sync + 'static,
+        ) -> Self {
+            self.options.stop_when = Some(Arc::new(predicate));
+            self
+        }
+
+        /// Only returns the entries ranked within `ranks`, counting from 1, such as `1..=10`
+        /// or `45..=55`. The range is positional: the entry at the start of the range is the
+        /// `ranks.start()`th one on the leaderboard, after `query` and `country` are applied.
+        ///
+        /// The pages covering the range are requested, and their entries sliced to exactly
+        /// that range. The limit still applies on top of it.
+        pub fn with_rank_range(mut self, ranks: impl RangeBounds<u32>) -> Self {
+            let start = match ranks.start_bound() {
+                Bound::Included(&start) => start,
+                Bound::Excluded(&start) => start.saturating_add(1),
+                Bound::Unbounded => 1,
+            };
+            let end = match ranks.end_bound() {
+                Bound::Included(&end) => end.saturating_add(1),
+                Bound::Excluded(&end) => end,
+                Bound::Unbounded => u32::MAX,
+            };
+            self.options.rank_range = Some(start..end);
+            self
+        }
     }
 
     impl LeaderboardQuery {
         /// Get the leaderboard data. Returns a stream of [`LeaderboardEntry`].
+        ///
+        /// Yields at most `limit` items. A `limit` of 0 yields nothing and sends no requests.
+        /// Items are yielded in the API's order: pages are fetched concurrently, but each page is
+        /// only yielded once all those before it have been, however their responses arrive.
         pub async fn get(
             self,
             limit: usize,
         ) -> Result<impl Stream<Item = Result<LeaderboardEntry>>> {
-            if self.leaderboard.is_none() {
-                bail!("missing leaderboard");
+            let (client, request) = self.paginate(limit)?;
+            let items = client.into_items(request).await?;
+            Ok(cancellable(items, self.options.cancellation))
+        }
+
+        /// Get the entry of `profile_id` along with up to `radius` entries ranked above and
+        /// below it, in rank order, like the player's position on the aoe4world site.
+        ///
+        /// The player's rank is looked up first, then the entries around it are fetched as
+        /// with [`Self::with_rank_range`]. Fewer entries are returned near the top or bottom
+        /// of the leaderboard. Ranks may shift between the two, in which case the player can
+        /// be off center. Both requests are for the same `season`. Fails if the player has no
+        /// entry on the leaderboard, or if `query`, `country` or a rank range are set, as
+        /// ranks are counted over the whole leaderboard.
+        pub async fn around(
+            self,
+            profile_id: impl Into<ProfileId>,
+            radius: u32,
+        ) -> Result<Vec<LeaderboardEntry>> {
+            let profile_id = profile_id.into();
+            if self.options.query.is_some()
+                || self.options.country.is_some()
+                || self.options.rank_range.is_some()
+            {
+                bail!("query, country and rank range cannot be combined with around");
             }
 
-            let client = PaginationClient::<LeaderboardPages, LeaderboardEntry>::with_limit(limit);
+            let entries = LeaderboardQuery::default()
+                .with_leaderboard(self.leaderboard)
+                .with_profile_id(profile_id)
+                .with_season(self.options.season)
+                .with_client(self.options.client.clone())
+                .with_cancellation(self.options.cancellation.clone())
+                .get(1)
+                .await?;
+            let rank = std::pin::pin!(entries)
+                .try_next()
+                .await?
+                .and_then(|entry| entry.rank);
+            let Some(rank) = rank else {
+                bail!("profile {profile_id} is not on the leaderboard");
+            };
+
+            let ranks = rank.saturating_sub(radius).max(1)..=rank.saturating_add(radius);
+            self.with_profile_id(None)
+                .with_rank_range(ranks)
+                .get(usize::MAX)
+                .await?
+                .try_collect()
+                .await
+        }
+
+        /// Get every entry on the leaderboard.
+        ///
+        /// Unlike [`Self::get`], there is no need to guess a limit up front: pages are
+        /// turned until the last one, however many items that takes.
+        pub async fn get_all(self) -> Result<impl Stream<Item = Result<LeaderboardEntry>>> {
+            let (client, request) = self.paginate(usize::MAX)?;
+            let items = client.into_all_items(request).await?;
+            Ok(cancellable(items, self.options.cancellation))
+        }
+
+        /// Get the leaderboard entries a page at a time, as they are returned by the server.
+        ///
+        /// Pages hold `page_size` items, except the last one, which may be cut short to
+        /// yield at most `limit` items in total.
+        pub async fn get_pages(
+            self,
+            limit: usize,
+        ) -> Result<impl Stream<Item = Result<Page<LeaderboardEntry>>>> {
+            let (client, request) = self.paginate(limit)?;
+            let pages = client.into_page_stream(request).await?;
+            Ok(cancellable(pages, self.options.cancellation))
+        }
 
+        /// Get the leaderboard data, along with pagination info such as the total number of
+        /// entries.
+        ///
+        /// The first page is fetched before returning, and its items are the first in the
+        /// stream. Yields at most `limit` items.
+        pub async fn get_with_meta(
+            self,
+            limit: usize,
+        ) -> Result<(PaginationMeta, impl Stream<Item = Result<LeaderboardEntry>>)> {
+            let (client, request) = self.paginate(limit)?;
+            let (meta, items) = client.into_items_with_meta(request).await?;
+            Ok((meta, cancellable(items, self.options.cancellation)))
+        }
+
+        /// Returns the URL that [`Self::get`] requests, without the `page` and `limit`
+        /// parameters added for each page. Useful to log or report the exact query sent to
+        /// the API.
+        pub fn build_url(&self) -> Result<Url> {
             let url = format!(
                 "https://aoe4world.com/api/v0/leaderboards/{}",
-                self.leaderboard.unwrap()
+                self.leaderboard
             )
             .parse()?;
-            let url = self.query_params(url);
+            Ok(self.query_params(url))
+        }
 
-            let pages = client
-                .into_pages_concurrent(PaginatedRequest::new(url))
-                .await?;
-            Ok(pages.items().take(limit))
+        /// Returns the client and first request used to paginate the query.
+        fn paginate(
+            &self,
+            limit: usize,
+        ) -> Result<(
+            PaginationClient<LeaderboardPages, LeaderboardEntry>,
+            PaginatedRequest,
+        )> {
+            let url = self.build_url()?;
+            let (limit, offset) = match &self.options.rank_range {
+                None => (limit, self.options.offset),
+                Some(_) if self.options.page.is_some() || self.options.offset.is_some() => {
+                    bail!("rank range cannot be combined with page or offset")
+                }
+                Some(ranks) if ranks.start == 0 => bail!("ranks count from 1"),
+                Some(ranks) => (limit.min(ranks.len()), Some(ranks.start - 1)),
+            };
+            let client = PaginationClient::<LeaderboardPages, LeaderboardEntry>::new(
+                self.options.client.clone().unwrap_or_default(),
+                limit,
+            )
+            .with_page_size(self.options.page_size, MAX_COUNT_PER_PAGE)
+            .with_start(self.options.page, offset)
+            .with_dedup(
+                self.options
+                    .dedup
+                    .unwrap_or_default()
+                    .then_some(|entry: &LeaderboardEntry| entry.profile_id.into()),
+            )
+            .with_page_failure(self.options.page_failure)
+            .with_stop_when(self.options.stop_when.clone(), self.options.stop_inclusive)
+            .with_page_delay(self.options.page_delay, self.options.page_delay_jitter);
+            Ok((client, PaginatedRequest::new(url)))
         }
 
         fn query_params(&self, mut url: Url) -> Url {
-            if let Some(query) = &self.query {
+            if let Some(query) = &self.options.query {
                 url.query_pairs_mut()
                     .append_pair("query", query.to_string().as_str());
             }
-            if let Some(profile_id) = self.profile_id {
+            if let Some(profile_id) = self.options.profile_id {
                 url.query_pairs_mut()
                     .append_pair("profile_id", profile_id.to_string().as_str());
             }
-            if let Some(country) = self.country {
+            if let Some(country) = self.options.country {
                 url.query_pairs_mut()
                     .append_pair("country", country.alpha2().to_lowercase().as_str());
             }
+            if let Some(season) = self.options.season {
+                url.query_pairs_mut()
+                    .append_pair("season", season.to_string().as_str());
+            }
             url
         }
     }
@@ -427,10 +1902,30 @@ pub mod query {
 mod tests {
     use super::*;
 
+    use std::{sync::Arc, time::Duration};
+
     use futures::StreamExt;
+    use reqwest::StatusCode;
+
+    use crate::{
+        client::Response,
+        pagination::Paginated,
+        testutils::{self, MockTransport},
+        types::{
+            games::{
+                Game, GameKind, GameResult, GameState, GamesOrder, GlobalGames, OrderDirection,
+                ProfileGames,
+            },
+            leaderboards::{LeaderboardEntry, LeaderboardPages},
+            maps::{Map, MapType},
+            search::SearchResults,
+        },
+    };
 
     const HOUSEDHORSE_ID: u64 = 3176;
+    const HOUSEDHORSE_STEAM_ID: &str = "76561198094298920";
     const ONLY_CAMS_ID: u64 = 10433860;
+    const NEPTUNE_ID: u64 = 4635035;
     const ONLY_CAMS_NAME: &str = "🐪🐪🐪OnlyCams🐪🐪🐪";
     const DEBILS_NAME: &str = "DEBILS";
 
@@ -446,6 +1941,161 @@ mod tests {
             .get()
             .await
             .expect("API call should succeed");
+
+        let err = profile(u64::MAX)
+            .get()
+            .await
+            .expect_err("API call should fail");
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::NotFound { .. })
+        ));
+
+        let p = profile(u64::MAX)
+            .get_optional()
+            .await
+            .expect("API call should succeed");
+        assert_eq!(None, p);
+    }
+
+    fn barbecue_profiles() -> Vec<Profile> {
+        let json_str = include_str!("../testdata/search/barbecue.json");
+        let results: SearchResults = serde_json::from_str(json_str).expect("should deserialize");
+        results.data()
+    }
+
+    #[tokio::test]
+    async fn test_find_steam_id() {
+        let profiles = barbecue_profiles();
+        let want = profiles[1].clone();
+        let steam_id = want.steam_id.clone().expect("fixture should have steam_id");
+
+        let found = find_steam_id(
+            futures::stream::iter(profiles.into_iter().map(Ok)),
+            &steam_id,
+        )
+        .await
+        .expect("should succeed");
+        assert_eq!(Some(want), found);
+
+        let found = find_steam_id(
+            futures::stream::iter(barbecue_profiles().into_iter().map(Ok)),
+            "1",
+        )
+        .await
+        .expect("should succeed");
+        assert_eq!(None, found);
+    }
+
+    #[tokio::test]
+    async fn test_find_players_exact() {
+        // (fixture, name, expected profile IDs)
+        let cases: [(&str, &str, &[u64]); 3] = [
+            (include_str!("../testdata/search/jigly.json"), "jigly", &[]),
+            (
+                include_str!("../testdata/search/onlycams.json"),
+                ONLY_CAMS_NAME,
+                &[ONLY_CAMS_ID],
+            ),
+            (
+                include_str!("../testdata/search/barbecue.json"),
+                "barbecue",
+                &[8588750, 1019252, 7411425],
+            ),
+        ];
+        for (fixture, name, want) in cases {
+            let results: SearchResults = serde_json::from_str(fixture).unwrap();
+            let profiles = exact_matches(
+                futures::stream::iter(results.data().into_iter().map(Ok)),
+                name,
+            )
+            .await
+            .expect("should succeed");
+            let ids: Vec<u64> = profiles.iter().map(|p| p.profile_id.into()).collect();
+            assert_eq!(want, ids, "{name}");
+            assert_eq!(
+                want.first().copied(),
+                most_recent(profiles).map(|p| p.profile_id.into()),
+                "{name}"
+            );
+        }
+
+        // The most recent player wins, whatever their position.
+        let mut profiles = barbecue_profiles();
+        profiles.retain(|p| p.name == "barbecue");
+        profiles.reverse();
+        assert_eq!(
+            Some(8588750),
+            most_recent(profiles).map(|p| u64::from(p.profile_id))
+        );
+        // Ties go to the first in search order.
+        let profiles = barbecue_profiles();
+        let latest = profiles.iter().map(|p| p.last_game_at).max().flatten();
+        let want = profiles
+            .iter()
+            .find(|p| p.last_game_at == latest)
+            .map(|p| p.profile_id);
+        assert_eq!(want, most_recent(profiles).map(|p| p.profile_id));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_search_first() {
+        let fixture = include_str!("../testdata/search/barbecue.json");
+        let transport = Arc::new(MockTransport::fixture(fixture));
+        let client = Client::with_transport(Client::builder(), transport.clone());
+        let profile = search("barbecue")
+            .with_client(Some(client))
+            .first()
+            .await
+            .expect("query should succeed");
+        assert_eq!(Some(barbecue_profiles()[0].clone()), profile);
+        assert_eq!(1, transport.requests().len());
+
+        let transport = Arc::new(MockTransport::pages(fixture, "players", 0, true));
+        let client = Client::with_transport(Client::builder(), transport);
+        let profile = search("nobody")
+            .with_client(Some(client))
+            .first()
+            .await
+            .expect("query should succeed");
+        assert_eq!(None, profile);
+    }
+
+    #[cfg_attr(not(feature = "test-api"), ignore)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn profile_by_steam_id_api_smoke() {
+        let profile = profile_by_steam_id(HOUSEDHORSE_STEAM_ID)
+            .await
+            .expect("API call should succeed")
+            .expect("profile should exist");
+        assert_eq!(HOUSEDHORSE_ID, u64::from(profile.profile_id));
+    }
+
+    #[cfg_attr(not(feature = "test-api"), ignore)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn find_player_api_smoke() {
+        let profile = find_player(ONLY_CAMS_NAME)
+            .await
+            .expect("API call should succeed")
+            .expect("profile should exist");
+        assert_eq!(ONLY_CAMS_ID, u64::from(profile.profile_id));
+    }
+
+    #[cfg_attr(not(feature = "test-api"), ignore)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn profiles_api_smoke() {
+        let p: Vec<_> = profiles([ONLY_CAMS_ID, HOUSEDHORSE_ID])
+            .get()
+            .await
+            .expect("API call should succeed")
+            .collect()
+            .await;
+        assert_eq!(2, p.len());
+        let ids: Vec<u64> = p
+            .into_iter()
+            .map(|p| p.expect("profile should be ok").profile_id.into())
+            .collect();
+        assert_eq!(vec![ONLY_CAMS_ID, HOUSEDHORSE_ID], ids);
     }
 
     #[cfg_attr(not(feature = "test-api"), ignore)]
@@ -485,6 +2135,54 @@ mod tests {
         }
     }
 
+    #[cfg_attr(not(feature = "test-api"), ignore)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn player_games_updated_since_api_smoke() {
+        let updated_since = chrono::Utc::now() - chrono::Duration::days(30);
+        let g: Vec<_> = profile_games(HOUSEDHORSE_ID)
+            .with_updated_since(Some(updated_since))
+            .get(10)
+            .await
+            .expect("API call should succeed")
+            .collect()
+            .await;
+        for (i, game) in g.iter().enumerate() {
+            let game = game
+                .as_ref()
+                .unwrap_or_else(|e| panic!("game {i} not ok: {e:?}"));
+            if let Some(updated_at) = game.updated_at {
+                assert!(updated_at >= updated_since, "game {i} updated too early");
+            }
+        }
+    }
+
+    #[cfg_attr(not(feature = "test-api"), ignore)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn player_latest_game_api_smoke() {
+        let game = ProfileId::from(HOUSEDHORSE_ID)
+            .latest_game()
+            .await
+            .expect("API call should succeed")
+            .expect("player should have played a game");
+        assert!(game.player(HOUSEDHORSE_ID).is_some());
+    }
+
+    #[cfg_attr(not(feature = "test-api"), ignore)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn game_api_smoke() {
+        let latest = ProfileId::from(HOUSEDHORSE_ID)
+            .latest_game()
+            .await
+            .expect("API call should succeed")
+            .expect("player should have played a game");
+        let got = game(HOUSEDHORSE_ID, latest.game_id)
+            .get()
+            .await
+            .expect("API call should succeed");
+        assert_eq!(latest.game_id, got.game_id);
+        assert!(got.player(HOUSEDHORSE_ID).is_some());
+    }
+
     #[cfg_attr(not(feature = "test-api"), ignore)]
     #[tokio::test(flavor = "multi_thread")]
     async fn global_games_api_smoke() {
@@ -501,6 +2199,2001 @@ mod tests {
         }
     }
 
+    #[cfg_attr(not(feature = "test-api"), ignore)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn global_games_partial_page_api_smoke() {
+        for limit in [1, 60, 125] {
+            let g: Vec<_> = global_games()
+                .get(limit)
+                .await
+                .expect("API call should succeed")
+                .collect()
+                .await;
+            assert_eq!(limit, g.len());
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_get_truncates_to_limit() {
+        // (limit, available, expected items, expected requests)
+        let cases = [
+            (0, 200, 0, 0),
+            (1, 200, 1, 1),
+            (30, 200, 30, 1),
+            (60, 200, 60, 2),
+            (125, 200, 125, 3),
+            (125, 70, 70, 3),
+        ];
+        for (limit, available, items, requests) in cases {
+            let transport = Arc::new(MockTransport::pages(
+                include_str!("../testdata/games/global.json"),
+                "games",
+                available,
+                true,
+            ));
+            let client = Client::with_transport(Client::builder(), transport.clone());
+
+            let g: Vec<_> = global_games()
+                .with_client(Some(client))
+                .get(limit)
+                .await
+                .expect("query should succeed")
+                .collect()
+                .await;
+            assert_eq!(items, g.len(), "limit {limit}, available {available}");
+            assert_eq!(
+                requests,
+                transport.requests().len(),
+                "limit {limit}, available {available}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_search_query_length() {
+        // (query, characters). The length is counted in characters, not bytes.
+        let cases = [
+            ("ab", 2),
+            ("abc", 3),
+            ("日本", 2),
+            ("日本語", 3),
+            ("🐪🐪", 2),
+            ("🐪🐪🐪", 3),
+            (ONLY_CAMS_NAME, 14),
+        ];
+        for (query, chars) in cases {
+            let res = search(query).build_url();
+            match chars {
+                3.. => assert!(res.is_ok(), "{query}"),
+                _ => assert_eq!(
+                    format!("search query must contain at least 3 characters, got {chars}"),
+                    res.unwrap_err().to_string(),
+                    "{query}"
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_search_query_encoding() {
+        let base = "https://aoe4world.com/api/v0/players/search";
+        let cases = [
+            ("rock & roll", "rock+%26+roll"),
+            ("#1 beasty", "%231+beasty"),
+            ("a=b?c", "a%3Db%3Fc"),
+            ("日本語", "%E6%97%A5%E6%9C%AC%E8%AA%9E"),
+        ];
+        for (query, want) in cases {
+            let url = search(query).build_url().expect("url should build");
+            assert_eq!(format!("{base}?query={want}"), url.as_str());
+            let pairs: Vec<_> = url.query_pairs().collect();
+            assert_eq!(vec![("query".into(), query.into())], pairs);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_search_filters() {
+        let profiles = barbecue_profiles();
+        fn rating(profile: &Profile, leaderboard: Leaderboard) -> Option<Option<i64>> {
+            profile
+                .modes
+                .as_ref()
+                .and_then(|modes| modes.get(leaderboard))
+                .map(|stats| stats.rating)
+        }
+        fn any_rating(profile: &Profile, min: i64) -> bool {
+            profile.modes.as_ref().is_some_and(|modes| {
+                modes
+                    .iter()
+                    .any(|(_, stats)| stats.rating.is_some_and(|r| r >= min))
+            })
+        }
+
+        // (leaderboard, min rating, expected profiles)
+        type Keep = fn(&Profile) -> bool;
+        let cases: [(_, _, Keep); 4] = [
+            (Some(Leaderboard::RmSolo), None, |p| {
+                rating(p, Leaderboard::RmSolo).is_some()
+            }),
+            (Some(Leaderboard::RmSolo), Some(500), |p| {
+                rating(p, Leaderboard::RmSolo).flatten() >= Some(500)
+            }),
+            (None, Some(1000), |p| any_rating(p, 1000)),
+            (Some(Leaderboard::QmFfa), None, |_| false),
+        ];
+        for (leaderboard, min_rating, keep) in cases {
+            let case = format!("leaderboard {leaderboard:?}, min rating {min_rating:?}");
+            let want: Vec<_> = profiles
+                .iter()
+                .filter(|p| keep(p))
+                .map(|p| p.profile_id)
+                .collect();
+            let transport = Arc::new(MockTransport::fixture(include_str!(
+                "../testdata/search/barbecue.json"
+            )));
+            let client = Client::with_transport(Client::builder(), transport.clone());
+
+            let got: Vec<_> = search("barbecue")
+                .with_leaderboard(leaderboard)
+                .with_min_rating(min_rating)
+                .with_client(Some(client))
+                .get(100)
+                .await
+                .expect("query should succeed")
+                .map(|p| p.expect("profile should be ok").profile_id)
+                .collect()
+                .await;
+            assert_eq!(want, got, "{case}");
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_profile_games_latest() {
+        let fixture = include_str!("../testdata/games/neptune.json");
+        let first = serde_json::from_str::<ProfileGames>(fixture)
+            .unwrap()
+            .data()[0]
+            .game_id;
+
+        let transport = Arc::new(MockTransport::fixture(fixture));
+        let client = Client::with_transport(Client::builder(), transport.clone());
+        let game = profile_games(NEPTUNE_ID)
+            .with_leaderboards([Leaderboard::RmSolo])
+            .with_opponent_profile_id(Some(HOUSEDHORSE_ID.into()))
+            .with_client(Some(client))
+            .latest()
+            .await
+            .expect("query should succeed");
+        assert_eq!(Some(first), game.map(|game| game.game_id));
+
+        let requests = transport.requests();
+        assert_eq!(1, requests.len());
+        let params: Vec<_> = requests[0].query_pairs().into_owned().collect();
+        for param in [
+            ("leaderboard", "rm_solo"),
+            ("opponent_profile_id", "3176"),
+            ("limit", "1"),
+        ] {
+            let param = (param.0.to_string(), param.1.to_string());
+            assert!(params.contains(&param), "missing {param:?}");
+        }
+
+        // No games at all.
+        let transport = Arc::new(MockTransport::pages(fixture, "games", 0, true));
+        let client = Client::with_transport(Client::builder(), transport);
+        let game = profile_games(NEPTUNE_ID)
+            .with_client(Some(client))
+            .latest()
+            .await
+            .expect("query should succeed");
+        assert_eq!(None, game);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_games_direction() {
+        let fixture = include_str!("../testdata/games/mixed_states.json");
+        let games = serde_json::from_str::<GlobalGames>(fixture).unwrap().data();
+        // 3 pages are served, each holding the same games.
+        let all: Vec<u32> = games
+            .iter()
+            .cycle()
+            .take(games.len() * 3)
+            .map(|game| game.game_id)
+            .collect();
+
+        // Reversed queries fetch the first page, then pages from the last one back until
+        // they hold enough games.
+        for (direction, limit, requests) in [
+            (None, 10, 1),
+            (Some(OrderDirection::Desc), 50, 1),
+            (Some(OrderDirection::Asc), 0, 0),
+            (Some(OrderDirection::Asc), 10, 2),
+            (Some(OrderDirection::Asc), 55, 3),
+            (Some(OrderDirection::Asc), 1000, 3),
+        ] {
+            let want: Vec<u32> = match direction {
+                Some(OrderDirection::Asc) => all.iter().rev().take(limit).copied().collect(),
+                _ => all.iter().take(limit).copied().collect(),
+            };
+            for profile in [false, true] {
+                let case = format!("direction {direction:?}, limit {limit}, profile {profile}");
+                let transport = Arc::new(MockTransport::pages(fixture, "games", all.len(), true));
+                let client = Client::with_transport(Client::builder(), transport.clone());
+                let query = match profile {
+                    true => profile_games(NEPTUNE_ID)
+                        .with_direction(direction)
+                        .with_client(Some(client))
+                        .get(limit)
+                        .await
+                        .map(|games| games.boxed()),
+                    false => global_games()
+                        .with_direction(direction)
+                        .with_client(Some(client))
+                        .get(limit)
+                        .await
+                        .map(|games| games.boxed()),
+                };
+                let got: Vec<u32> = query
+                    .expect("query should succeed")
+                    .map(|game| game.expect("game should be ok").game_id)
+                    .collect()
+                    .await;
+                assert_eq!(want, got, "{case}");
+                // The direction is applied client-side, so it is not sent to the API.
+                assert!(
+                    transport
+                        .requests()
+                        .iter()
+                        .all(|url| url.query_pairs().all(|(k, _)| k != "direction")),
+                    "{case}"
+                );
+                assert_eq!(requests, transport.requests().len(), "{case}");
+            }
+        }
+
+        // Without a total count, there is no telling which page is the last one.
+        let transport = Arc::new(MockTransport::pages(fixture, "games", all.len(), false));
+        let client = Client::with_transport(Client::builder(), transport);
+        assert!(global_games()
+            .with_direction(Some(OrderDirection::Asc))
+            .with_client(Some(client))
+            .get(10)
+            .await
+            .is_err());
+
+        // Filters apply to the reversed games.
+        let transport = Arc::new(MockTransport::pages(fixture, "games", all.len(), true));
+        let client = Client::with_transport(Client::builder(), transport);
+        let got: Vec<Game> = global_games()
+            .with_direction(Some(OrderDirection::Asc))
+            .with_state(Some(vec![GameState::Finished]))
+            .with_client(Some(client))
+            .get_all()
+            .await
+            .expect("query should succeed")
+            .map(|game| game.expect("game should be ok"))
+            .collect()
+            .await;
+        let want: Vec<u32> = all
+            .iter()
+            .rev()
+            .filter(|id| {
+                games
+                    .iter()
+                    .any(|game| game.game_id == **id && game.state() == GameState::Finished)
+            })
+            .copied()
+            .collect();
+        assert_eq!(
+            want,
+            got.iter().map(|game| game.game_id).collect::<Vec<_>>()
+        );
+
+        // Streams that cannot reverse their items refuse to.
+        let query = || profile_games(NEPTUNE_ID).with_direction(Some(OrderDirection::Asc));
+        assert!(query().get_pages(10).await.is_err());
+        assert!(query().get_with_meta(10).await.is_err());
+        assert!(query().get_resumable().await.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_games_season() {
+        let fixture = include_str!("../testdata/games/mixed_states.json");
+        let games: GlobalGames = serde_json::from_str(fixture).unwrap();
+        let games = games.data();
+        let cases = [vec![3], vec![6], vec![0, 6], vec![5], vec![]];
+        for seasons in cases {
+            let want: Vec<u32> = games
+                .iter()
+                .filter(|game| game.season.is_some_and(|s| seasons.contains(&s)))
+                .map(|game| game.game_id)
+                .take(10)
+                .collect();
+            for profile in [false, true] {
+                let case = format!("seasons {seasons:?}, profile {profile}");
+                let transport = Arc::new(MockTransport::pages(fixture, "games", 40, true));
+                let client = Client::with_transport(Client::builder(), transport.clone());
+                let query = match profile {
+                    true => profile_games(NEPTUNE_ID)
+                        .with_season(Some(seasons.clone()))
+                        .with_client(Some(client))
+                        .get(10)
+                        .await
+                        .map(|games| games.boxed()),
+                    false => global_games()
+                        .with_season(Some(seasons.clone()))
+                        .with_client(Some(client))
+                        .get(10)
+                        .await
+                        .map(|games| games.boxed()),
+                };
+                let got: Vec<u32> = query
+                    .expect("query should succeed")
+                    .map(|game| game.expect("game should be ok").game_id)
+                    .collect()
+                    .await;
+                // The limit counts games in the requested seasons.
+                assert_eq!(want, got, "{case}");
+
+                // The season is filtered client-side, so it is not sent to the API.
+                assert!(
+                    transport
+                        .requests()
+                        .iter()
+                        .all(|url| url.query_pairs().all(|(k, _)| k != "season")),
+                    "{case}"
+                );
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_games_only_ongoing_finished() {
+        let fixture = include_str!("../testdata/games/mixed_states.json");
+        let games: GlobalGames = serde_json::from_str(fixture).unwrap();
+        let games = games.data();
+        let ids = |keep: fn(&Game) -> bool| -> Vec<u32> {
+            games
+                .iter()
+                .filter(|game| keep(game))
+                .map(|game| game.game_id)
+                .collect()
+        };
+        let cases = [
+            (
+                global_games().only_ongoing(true),
+                ids(|game| game.ongoing == Some(true)),
+            ),
+            (
+                global_games().only_finished(true),
+                ids(|game| game.ongoing != Some(true)),
+            ),
+            (
+                global_games()
+                    .with_state(Some(vec![GameState::Finished]))
+                    .only_ongoing(true),
+                ids(|game| game.ongoing == Some(true)),
+            ),
+            (
+                global_games().only_ongoing(true).only_ongoing(false),
+                ids(|_| true),
+            ),
+        ];
+        for (i, (query, want)) in cases.into_iter().enumerate() {
+            assert!(!want.is_empty(), "case {i}");
+            let transport = Arc::new(MockTransport::pages(fixture, "games", games.len(), true));
+            let client = Client::with_transport(Client::builder(), transport);
+            let got: Vec<u32> = query
+                .with_order(Some(GamesOrder::StartedAt))
+                .with_client(Some(client))
+                .get(100)
+                .await
+                .expect("query should succeed")
+                .map(|game| game.expect("game should be ok").game_id)
+                .collect()
+                .await;
+            assert_eq!(want, got, "case {i}");
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_games_state() {
+        let fixture = include_str!("../testdata/games/mixed_states.json");
+        let games: GlobalGames = serde_json::from_str(fixture).unwrap();
+        let games = games.data();
+        let cases = [
+            vec![GameState::Ongoing],
+            vec![GameState::JustFinished],
+            vec![GameState::Finished],
+            vec![GameState::Ongoing, GameState::JustFinished],
+            vec![],
+        ];
+        for states in cases {
+            let want: Vec<u32> = games
+                .iter()
+                .filter(|game| states.contains(&game.state()))
+                .map(|game| game.game_id)
+                .collect();
+            for profile in [false, true] {
+                let case = format!("states {states:?}, profile {profile}");
+                let transport = Arc::new(MockTransport::pages(fixture, "games", 40, true));
+                let client = Client::with_transport(Client::builder(), transport.clone());
+                let query = match profile {
+                    true => profile_games(NEPTUNE_ID)
+                        .with_state(Some(states.clone()))
+                        .with_client(Some(client))
+                        .get(100)
+                        .await
+                        .map(|games| games.boxed()),
+                    false => global_games()
+                        .with_state(Some(states.clone()))
+                        .with_client(Some(client))
+                        .get(100)
+                        .await
+                        .map(|games| games.boxed()),
+                };
+                let got: Vec<u32> = query
+                    .expect("query should succeed")
+                    .map(|game| game.expect("game should be ok").game_id)
+                    .collect()
+                    .await;
+                assert_eq!(want, got, "{case}");
+
+                // The state is filtered client-side, so it is not sent to the API.
+                let requests = transport.requests();
+                assert!(
+                    requests
+                        .iter()
+                        .all(|url| url.query_pairs().all(|(k, _)| k != "state")),
+                    "{case}"
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_game() {
+        let url = game(NEPTUNE_ID, 56783543)
+            .build_url()
+            .expect("url should build");
+        assert_eq!(
+            "https://aoe4world.com/api/v0/players/4635035/games/56783543",
+            url.as_str()
+        );
+        assert_eq!(
+            "missing game_id",
+            GameQuery::default()
+                .with_profile_id(Some(NEPTUNE_ID.into()))
+                .build_url()
+                .unwrap_err()
+                .to_string()
+        );
+
+        let transport = Arc::new(MockTransport::fixture(include_str!(
+            "../testdata/games/neptune_game_from_list.json"
+        )));
+        let client = Client::with_transport(Client::builder(), transport);
+        let got = game(NEPTUNE_ID, 56783543)
+            .with_client(Some(client))
+            .get()
+            .await
+            .expect("query should succeed");
+        assert_eq!(56783543, got.game_id);
+        assert_eq!(Some(GameResult::Loss), got.result_for(NEPTUNE_ID));
+
+        // Unknown game IDs are not found.
+        let transport = Arc::new(MockTransport::new(|_| Response {
+            status: StatusCode::NOT_FOUND,
+            body: include_str!("../testdata/profile/not_found.json").into(),
+        }));
+        let client = Client::with_transport(Client::builder(), transport);
+        let err = game(NEPTUNE_ID, 1)
+            .with_client(Some(client.clone()))
+            .get()
+            .await
+            .expect_err("query should fail");
+        assert!(
+            matches!(err.downcast_ref(), Some(Error::NotFound { .. })),
+            "{err:?}"
+        );
+        let got = game(NEPTUNE_ID, 1)
+            .with_client(Some(client))
+            .get_optional()
+            .await
+            .expect("query should succeed");
+        assert_eq!(None, got);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_games_maps() {
+        let fixture = include_str!("../testdata/games/mixed_states.json");
+        let games: GlobalGames = serde_json::from_str(fixture).unwrap();
+        let games = games.data();
+        let on_map = |maps: &[Map]| {
+            let maps = maps.to_vec();
+            move |game: &&Game| game.map.as_ref().is_some_and(|map| maps.contains(map))
+        };
+        let of_type = |types: &[MapType]| {
+            let types = types.to_vec();
+            move |game: &&Game| {
+                game.map
+                    .as_ref()
+                    .is_some_and(|map| types.contains(&map.map_type()))
+            }
+        };
+
+        // (maps, map types, limit); the fixture is served as 3 pages of 40 games.
+        let cases = [
+            (Some(vec![Map::DryArabia]), None, 2),
+            (Some(vec![Map::DryArabia, Map::Wetlands]), None, 12),
+            (Some(vec![Map::DryArabia]), None, 100),
+            (None, Some(vec![MapType::Water]), 100),
+            (None, Some(vec![MapType::Land, MapType::Hybrid]), 30),
+            (Some(vec![Map::Lipany]), Some(vec![MapType::Water]), 10),
+        ];
+        for (maps, map_types, limit) in cases {
+            let mut want = games.iter().collect::<Vec<_>>();
+            if let Some(ref maps) = maps {
+                want.retain(on_map(maps));
+            }
+            if let Some(ref map_types) = map_types {
+                want.retain(of_type(map_types));
+            }
+            // Every page holds the same games, so the fixture repeats 3 times.
+            let want: Vec<u32> = want
+                .iter()
+                .cycle()
+                .take(want.len() * 3)
+                .take(limit)
+                .map(|game| game.game_id)
+                .collect();
+
+            for profile in [false, true] {
+                let case = format!("maps {maps:?}, map types {map_types:?}, profile {profile}");
+                let transport = Arc::new(MockTransport::pages(fixture, "games", 120, true));
+                let client = Client::with_transport(Client::builder(), transport.clone());
+                let query = match profile {
+                    true => {
+                        let mut query = profile_games(NEPTUNE_ID).with_client(Some(client));
+                        if let Some(maps) = maps.clone() {
+                            query = query.with_maps(maps);
+                        }
+                        if let Some(map_types) = map_types.clone() {
+                            query = query.with_map_types(map_types);
+                        }
+                        query.get(limit).await.map(|games| games.boxed())
+                    }
+                    false => {
+                        let mut query = global_games().with_client(Some(client));
+                        if let Some(maps) = maps.clone() {
+                            query = query.with_maps(maps);
+                        }
+                        if let Some(map_types) = map_types.clone() {
+                            query = query.with_map_types(map_types);
+                        }
+                        query.get(limit).await.map(|games| games.boxed())
+                    }
+                };
+                let got: Vec<u32> = query
+                    .expect("query should succeed")
+                    .map(|game| game.expect("game should be ok").game_id)
+                    .collect()
+                    .await;
+                assert_eq!(want, got, "{case}");
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_games_until() {
+        let fixture = include_str!("../testdata/games/neptune.json");
+        let games = serde_json::from_str::<ProfileGames>(fixture)
+            .unwrap()
+            .data();
+        let started: Vec<_> = games.iter().map(|game| game.started_at.unwrap()).collect();
+        let page_size = started.len();
+        let until = started[30];
+        let since = started[40];
+        let per_page = started.iter().filter(|&&t| t < until).count();
+        assert_eq!(page_size - 31, per_page, "fixture should be newest first");
+
+        // (limit, expected items, expected requests); 4 pages are served. Past the first
+        // page, filtered queries read the remaining pages ahead.
+        let cases = [
+            (1, 1, 1),
+            (per_page, per_page, 1),
+            (per_page + 1, per_page + 1, 4),
+            (1000, per_page * 4, 4),
+        ];
+        for (limit, items, requests) in cases {
+            for profile in [false, true] {
+                let case = format!("limit {limit}, profile {profile}");
+                let transport =
+                    Arc::new(MockTransport::pages(fixture, "games", page_size * 4, true));
+                let client = Client::with_transport(Client::builder(), transport.clone());
+                let query = match profile {
+                    true => profile_games(NEPTUNE_ID)
+                        .with_until(Some(until))
+                        .with_client(Some(client))
+                        .get(limit)
+                        .await
+                        .map(|games| games.boxed()),
+                    false => global_games()
+                        .with_until(Some(until))
+                        .with_client(Some(client))
+                        .get(limit)
+                        .await
+                        .map(|games| games.boxed()),
+                };
+                let games: Vec<Game> = query
+                    .expect("query should succeed")
+                    .map(|game| game.expect("game should be ok"))
+                    .collect()
+                    .await;
+                assert_eq!(items, games.len(), "{case}");
+                assert!(
+                    games.iter().all(|game| game.started_at.unwrap() < until),
+                    "{case}: game started after until"
+                );
+                assert_eq!(requests, transport.requests().len(), "{case}");
+                // `until` is filtered client-side, so it is not sent to the API.
+                assert!(
+                    transport
+                        .requests()
+                        .iter()
+                        .all(|url| url.query_pairs().all(|(k, _)| k != "until")),
+                    "{case}"
+                );
+            }
+        }
+
+        // With `since` as well, `since` is sent to the API while `until` is applied to
+        // what it returns. The mock ignores `since`, so only `until` drops games here.
+        let want: Vec<u32> = games
+            .iter()
+            .filter(|game| game.started_at.unwrap() < until)
+            .map(|game| game.game_id)
+            .collect();
+        let transport = Arc::new(MockTransport::pages(fixture, "games", page_size, true));
+        let client = Client::with_transport(Client::builder(), transport.clone());
+        let got: Vec<u32> = profile_games(NEPTUNE_ID)
+            .with_since(Some(since))
+            .with_until(Some(until))
+            .with_client(Some(client))
+            .get(100)
+            .await
+            .expect("query should succeed")
+            .map(|game| game.expect("game should be ok").game_id)
+            .collect()
+            .await;
+        assert_eq!(want, got);
+        let since_param = since.to_rfc3339();
+        assert!(transport.requests()[0]
+            .query_pairs()
+            .any(|(k, v)| k == "since" && v == since_param));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_games_duration() {
+        let fixture = include_str!("../testdata/games/mixed_states.json");
+        let games: GlobalGames = serde_json::from_str(fixture).unwrap();
+        let durations: Vec<Option<u32>> = games.data().iter().map(|game| game.duration).collect();
+        assert!(
+            durations.contains(&None),
+            "fixture should have ongoing games"
+        );
+
+        let minutes = |n: u64| Duration::from_secs(n * 60);
+        // (min, max, expected durations in seconds, sorted). Bounds are inclusive.
+        let cases = [
+            (
+                Some(minutes(20)),
+                None,
+                vec![1220, 1344, 1401, 1420, 1450, 1507, 1624, 1706, 2026, 2047],
+            ),
+            (
+                None,
+                Some(Duration::from_secs(847)),
+                vec![385, 760, 815, 847],
+            ),
+            (
+                Some(Duration::from_secs(1450)),
+                Some(Duration::from_secs(1450)),
+                vec![1450],
+            ),
+            (
+                Some(Duration::from_millis(1_449_500)),
+                Some(Duration::from_millis(1_450_500)),
+                vec![1450],
+            ),
+            (Some(minutes(60)), None, vec![]),
+            (Some(minutes(30)), Some(minutes(10)), vec![]),
+        ];
+        for (min, max, want) in cases {
+            for profile in [false, true] {
+                let case = format!("min {min:?}, max {max:?}, profile {profile}");
+                let transport = Arc::new(MockTransport::pages(fixture, "games", 40, true));
+                let client = Client::with_transport(Client::builder(), transport.clone());
+                let query = match profile {
+                    true => profile_games(NEPTUNE_ID)
+                        .with_min_duration(min)
+                        .with_max_duration(max)
+                        .with_client(Some(client))
+                        .get(100)
+                        .await
+                        .map(|games| games.boxed()),
+                    false => global_games()
+                        .with_min_duration(min)
+                        .with_max_duration(max)
+                        .with_client(Some(client))
+                        .get(100)
+                        .await
+                        .map(|games| games.boxed()),
+                };
+                let mut got: Vec<u32> = query
+                    .expect("query should succeed")
+                    .map(|game| {
+                        game.expect("game should be ok")
+                            .duration
+                            .expect("games without a duration should be dropped")
+                    })
+                    .collect()
+                    .await;
+                got.sort();
+                assert_eq!(want, got, "{case}");
+            }
+        }
+
+        // Without bounds, games without a duration are kept.
+        let transport = Arc::new(MockTransport::pages(fixture, "games", 40, true));
+        let client = Client::with_transport(Client::builder(), transport.clone());
+        let count = global_games()
+            .with_client(Some(client))
+            .get(100)
+            .await
+            .expect("query should succeed")
+            .count()
+            .await;
+        assert_eq!(durations.len(), count);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_global_games_average_rating() {
+        let fixture = include_str!("../testdata/games/mixed_states.json");
+        let games: GlobalGames = serde_json::from_str(fixture).unwrap();
+        let ratings: Vec<Option<f64>> = games
+            .data()
+            .iter()
+            .map(|game| game.average_rating)
+            .collect();
+        assert!(ratings.contains(&None), "fixture should have unrated games");
+
+        // (min, max, expected ratings, sorted). Bounds are inclusive.
+        let cases = [
+            (
+                Some(1822.0),
+                None,
+                vec![
+                    1822.0, 1822.0, 1824.0, 1824.0, 1827.0, 1833.0, 1845.0, 1919.0,
+                ],
+            ),
+            (None, Some(1475.0), vec![1424.0, 1451.0, 1475.0]),
+            (Some(1822.0), Some(1822.0), vec![1822.0, 1822.0]),
+            (Some(1610.0), Some(1632.0), vec![1610.0, 1614.0, 1632.0]),
+            (Some(1900.5), Some(1900.5), vec![]),
+            (Some(2000.0), Some(1000.0), vec![]),
+        ];
+        for (min, max, want) in cases {
+            let case = format!("min {min:?}, max {max:?}");
+            let transport = Arc::new(MockTransport::pages(fixture, "games", 40, true));
+            let client = Client::with_transport(Client::builder(), transport.clone());
+
+            let mut got: Vec<f64> = global_games()
+                .with_min_average_rating(min)
+                .with_max_average_rating(max)
+                .with_client(Some(client))
+                .get(100)
+                .await
+                .expect("query should succeed")
+                .map(|game| {
+                    game.expect("game should be ok")
+                        .average_rating
+                        .expect("unrated games should be dropped")
+                })
+                .collect()
+                .await;
+            got.sort_by(f64::total_cmp);
+            assert_eq!(want, got, "{case}");
+        }
+
+        // Without bounds, unrated games are kept.
+        let transport = Arc::new(MockTransport::pages(fixture, "games", 40, true));
+        let client = Client::with_transport(Client::builder(), transport.clone());
+        let count = global_games()
+            .with_client(Some(client))
+            .get(100)
+            .await
+            .expect("query should succeed")
+            .count()
+            .await;
+        assert_eq!(ratings.len(), count);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_global_games_dedup() {
+        let fixture = include_str!("../testdata/games/global.json");
+        let want: Vec<u32> = serde_json::from_str::<GlobalGames>(fixture)
+            .unwrap()
+            .data()
+            .iter()
+            .map(|game| game.game_id)
+            .collect();
+
+        for dedup in [false, true] {
+            // Each page repeats the last 5 games of the previous one.
+            let transport = Arc::new(MockTransport::shifting_pages(
+                fixture,
+                "games",
+                want.len(),
+                true,
+                5,
+            ));
+            let client = Client::with_transport(Client::builder(), transport.clone());
+
+            let ids: Vec<u32> = global_games()
+                .with_page_size(Some(20))
+                .with_dedup(Some(dedup))
+                .with_client(Some(client))
+                .get(want.len())
+                .await
+                .expect("query should succeed")
+                .map(|game| game.expect("game should be ok").game_id)
+                .collect()
+                .await;
+            match dedup {
+                true => assert_eq!(want, ids, "duplicates should be dropped"),
+                false => {
+                    assert_eq!(want.len(), ids.len());
+                    assert_eq!(ids[15..20], ids[20..25], "page 2 should overlap page 1");
+                }
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_page_failure() {
+        // (policy, failures of page 14, expected games, expected errors)
+        let cases = [
+            (None, usize::MAX, 13 * 50, 1),
+            (
+                Some(PageFailure::SkipAfterRetries(3)),
+                usize::MAX,
+                39 * 50,
+                1,
+            ),
+            (Some(PageFailure::SkipAfterRetries(3)), 3, 40 * 50, 0),
+        ];
+        for (policy, failures, games, errors) in cases {
+            let case = format!("policy {policy:?}, failures {failures}");
+            let transport = Arc::new(
+                MockTransport::pages(
+                    include_str!("../testdata/games/global.json"),
+                    "games",
+                    40 * 50,
+                    true,
+                )
+                .failing_page(14, failures),
+            );
+            let client = Client::with_transport(Client::builder(), transport.clone());
+
+            let items: Vec<_> = global_games()
+                .with_page_failure(policy)
+                .with_client(Some(client))
+                .get_all()
+                .await
+                .expect("query should succeed")
+                .collect()
+                .await;
+            let failed: Vec<_> = items
+                .iter()
+                .filter_map(|item| item.as_ref().err())
+                .collect();
+            assert_eq!(games, items.len() - failed.len(), "{case}");
+            assert_eq!(errors, failed.len(), "{case}");
+            if let Some(err) = failed.first() {
+                assert!(format!("{err:#}").contains("500"), "{case}: {err:#}");
+            }
+            if policy.is_some() && errors > 0 {
+                assert!(format!("{:#}", failed[0]).contains("page 14"), "{case}");
+                let position = items.iter().position(Result::is_err).unwrap();
+                assert_eq!(13 * 50, position, "{case}: error should replace page 14");
+            }
+
+            let page_14 = transport
+                .requests()
+                .iter()
+                .filter(|url| url.query_pairs().any(|(k, v)| k == "page" && v == "14"))
+                .count();
+            let want = match policy {
+                None => 1,
+                Some(_) => failures.min(3) + 1,
+            };
+            assert_eq!(want, page_14, "{case}: page 14 requests");
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_page_failure_every_page() {
+        // Every page after the first fails, so the stream cannot tell where the end is.
+        for report_total in [true, false] {
+            let transport = Arc::new(
+                MockTransport::pages(
+                    include_str!("../testdata/games/global.json"),
+                    "games",
+                    40 * 50,
+                    report_total,
+                )
+                .with_route(
+                    |url| !url.query_pairs().any(|(k, v)| k == "page" && v == "1"),
+                    |_| Response {
+                        status: StatusCode::INTERNAL_SERVER_ERROR,
+                        body: "internal server error".into(),
+                    },
+                ),
+            );
+            let client = Client::with_transport(Client::builder(), transport.clone());
+
+            let items: Vec<_> = global_games()
+                .with_page_failure(Some(PageFailure::SkipAfterRetries(0)))
+                .with_client(Some(client))
+                .get_all()
+                .await
+                .expect("query should succeed")
+                .collect()
+                .await;
+            let errors = items.iter().filter(|item| item.is_err()).count();
+            assert_eq!(50, items.len() - errors, "report total {report_total}");
+            // Without a total, pages are fetched one at a time, so exactly 10 are skipped.
+            match report_total {
+                true => assert!((1..=10).contains(&errors), "{errors} errors"),
+                false => assert_eq!(10, errors),
+            }
+            assert!(
+                transport.requests().len() < 40,
+                "report total {report_total}: skipping should stop before the last page"
+            );
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_page_failure_rate_limited() {
+        // Page 2 is rate limited once, which is retried like a server error.
+        let limited = std::sync::atomic::AtomicBool::new(false);
+        let transport = Arc::new(
+            MockTransport::pages(
+                include_str!("../testdata/games/global.json"),
+                "games",
+                3 * 50,
+                true,
+            )
+            .with_route(
+                move |url| {
+                    url.query_pairs().any(|(k, v)| k == "page" && v == "2")
+                        && !limited.swap(true, std::sync::atomic::Ordering::SeqCst)
+                },
+                |_| Response {
+                    status: StatusCode::TOO_MANY_REQUESTS,
+                    body: "slow down".into(),
+                },
+            ),
+        );
+        let client = Client::with_transport(Client::builder(), transport.clone());
+
+        let items: Vec<_> = global_games()
+            .with_page_failure(Some(PageFailure::SkipAfterRetries(1)))
+            .with_client(Some(client))
+            .get_all()
+            .await
+            .expect("query should succeed")
+            .collect()
+            .await;
+        assert!(
+            items.iter().all(Result::is_ok),
+            "rate limit should be retried"
+        );
+        assert_eq!(3 * 50, items.len());
+        assert_eq!(4, transport.requests().len());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stop_when() {
+        let cutoff: chrono::DateTime<chrono::Utc> = "2022-12-17T17:00:00Z".parse().unwrap();
+        let query = |transport: Arc<MockTransport>, inclusive| {
+            profile_games(NEPTUNE_ID)
+                .with_page_size(Some(10))
+                .with_stop_when(move |game: &Game| game.started_at < Some(cutoff))
+                .with_stop_inclusive(Some(inclusive))
+                .with_client(Some(Client::with_transport(Client::builder(), transport)))
+        };
+        let neptune = |report_total| {
+            Arc::new(MockTransport::pages(
+                include_str!("../testdata/games/neptune.json"),
+                "games",
+                120,
+                report_total,
+            ))
+        };
+
+        for inclusive in [true, false] {
+            let transport = neptune(false);
+            let games: Vec<Game> = query(transport.clone(), inclusive)
+                .get_all()
+                .await
+                .expect("query should succeed")
+                .map(|game| game.expect("game should be ok"))
+                .collect()
+                .await;
+            let want = if inclusive { 11 } else { 10 };
+            assert_eq!(want, games.len(), "inclusive {inclusive}");
+            assert!(games[..10]
+                .iter()
+                .all(|game| game.started_at >= Some(cutoff)));
+            assert_eq!(2, transport.requests().len(), "inclusive {inclusive}");
+
+            let transport = neptune(true);
+            let games: Vec<_> = query(transport.clone(), inclusive)
+                .get(100)
+                .await
+                .expect("query should succeed")
+                .collect()
+                .await;
+            assert_eq!(want, games.len(), "inclusive {inclusive}");
+            assert!(
+                transport.requests().len() < 10,
+                "later pages should not be requested"
+            );
+
+            let pages: Vec<(u32, usize)> = query(neptune(false), inclusive)
+                .get_pages(100)
+                .await
+                .expect("query should succeed")
+                .map(|page| page.expect("page should be ok"))
+                .map(|page| (page.number, page.items.len()))
+                .collect()
+                .await;
+            let want = if inclusive {
+                vec![(1, 10), (2, 1)]
+            } else {
+                vec![(1, 10)]
+            };
+            assert_eq!(want, pages, "inclusive {inclusive}");
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_resume() {
+        let fixture = include_str!("../testdata/games/global.json");
+        let ids: Vec<u32> = serde_json::from_str::<GlobalGames>(fixture)
+            .unwrap()
+            .data()
+            .iter()
+            .map(|game| game.game_id)
+            .collect();
+        let want: Vec<u32> = (0..200).map(|i| ids[i % ids.len()]).collect();
+        let client = || {
+            let transport = Arc::new(MockTransport::pages(fixture, "games", 200, false));
+            Client::with_transport(Client::builder(), transport)
+        };
+
+        // Stop right after page 2, and part way through page 3.
+        for consumed in [100, 105] {
+            let (items, handle) = global_games()
+                .with_client(Some(client()))
+                .get_resumable()
+                .await
+                .expect("query should succeed");
+            let mut got: Vec<u32> = items
+                .take(consumed)
+                .map(|game| game.expect("game should be ok").game_id)
+                .collect()
+                .await;
+
+            let cursor = serde_json::to_string(&handle.get()).expect("cursor should serialize");
+            let cursor: Cursor = serde_json::from_str(&cursor).expect("cursor should deserialize");
+            let page = (consumed - 1) / 50 + 1;
+            assert_eq!(page as u32, cursor.page, "consumed {consumed}");
+            assert_eq!(
+                Some(u64::from(want[consumed - 1])),
+                cursor.last_key,
+                "consumed {consumed}"
+            );
+
+            let (rest, handle) = resume(cursor)
+                .with_client(Some(client()))
+                .get_resumable()
+                .await
+                .expect("resume should succeed");
+            got.extend(
+                rest.map(|game| game.expect("game should be ok").game_id)
+                    .collect::<Vec<_>>()
+                    .await,
+            );
+            assert_eq!(want, got, "consumed {consumed}");
+            assert_eq!(5, handle.get().page, "consumed {consumed}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume_invalid_cursor() {
+        let cursor = Cursor {
+            url: "https://aoe4world.com/api/v0/players/search"
+                .parse()
+                .unwrap(),
+            per_page: 50,
+            page: 1,
+            last_key: None,
+        };
+        resume(cursor)
+            .get_resumable()
+            .await
+            .err()
+            .expect("cursor should be rejected");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_profile_games_since_params() {
+        let since: chrono::DateTime<chrono::Utc> = "2024-03-01T00:00:00Z".parse().unwrap();
+        let updated_since: chrono::DateTime<chrono::Utc> = "2024-03-18T12:30:00Z".parse().unwrap();
+        // (since, updated_since, expected params)
+        let cases = [
+            (None, None, vec![]),
+            (
+                Some(since),
+                None,
+                vec![("since", "2024-03-01T00:00:00+00:00")],
+            ),
+            (
+                None,
+                Some(updated_since),
+                vec![("updated_since", "2024-03-18T12:30:00+00:00")],
+            ),
+            (
+                Some(since),
+                Some(updated_since),
+                vec![
+                    ("since", "2024-03-01T00:00:00+00:00"),
+                    ("updated_since", "2024-03-18T12:30:00+00:00"),
+                ],
+            ),
+        ];
+        for (since, updated_since, want) in cases {
+            let transport = Arc::new(MockTransport::fixture(include_str!(
+                "../testdata/games/neptune.json"
+            )));
+            let client = Client::with_transport(Client::builder(), transport.clone());
+
+            profile_games(NEPTUNE_ID)
+                .with_since(since)
+                .with_updated_since(updated_since)
+                .with_client(Some(client))
+                .get(1)
+                .await
+                .expect("query should succeed")
+                .collect::<Vec<_>>()
+                .await;
+
+            let requests = transport.requests();
+            let got: Vec<_> = requests[0]
+                .query_pairs()
+                .filter(|(k, _)| k.ends_with("since"))
+                .collect();
+            let want: Vec<_> = want
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect();
+            assert_eq!(
+                want, got,
+                "since {since:?}, updated_since {updated_since:?}"
+            );
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_leaderboard_country() {
+        let base = "https://aoe4world.com/api/v0/leaderboards/rm_solo";
+        let cases = [
+            (
+                leaderboard(Leaderboard::RmSolo),
+                format!("{base}?country=ca"),
+            ),
+            (
+                leaderboard(Leaderboard::RmSolo).with_query(Some("beasty".to_string())),
+                format!("{base}?query=beasty&country=ca"),
+            ),
+            (
+                leaderboard(Leaderboard::RmSolo).with_profile_id(Some(NEPTUNE_ID.into())),
+                format!("{base}?profile_id=4635035&country=ca"),
+            ),
+        ];
+        for (query, want) in cases {
+            let url = query
+                .with_country(CountryCode::CAN)
+                .build_url()
+                .expect("url should build");
+            assert_eq!(want, url.as_str());
+        }
+
+        let transport = Arc::new(MockTransport::fixture(include_str!(
+            "../testdata/leaderboards/rm_solo_country.json"
+        )));
+        let client = Client::with_transport(Client::builder(), transport.clone());
+        let entries: Vec<LeaderboardEntry> = leaderboard(Leaderboard::RmSolo)
+            .with_country(CountryCode::CAN)
+            .with_client(Some(client))
+            .get(100)
+            .await
+            .expect("query should succeed")
+            .map(|entry| entry.expect("entry should be ok"))
+            .collect()
+            .await;
+        assert_eq!(5, entries.len());
+        assert!(entries
+            .iter()
+            .all(|entry| entry.country == Some(CountryCode::CAN)));
+        assert!(transport
+            .requests()
+            .iter()
+            .all(|url| url.query_pairs().any(|(k, v)| k == "country" && v == "ca")));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_leaderboard_season() {
+        let base = "https://aoe4world.com/api/v0/leaderboards/rm_solo";
+        let url = leaderboard(Leaderboard::RmSolo)
+            .with_season(7)
+            .build_url()
+            .expect("url should build");
+        assert_eq!(format!("{base}?season=7"), url.as_str());
+        let url = leaderboard(Leaderboard::RmSolo)
+            .with_country(CountryCode::CAN)
+            .with_season(7)
+            .build_url()
+            .expect("url should build");
+        assert_eq!(format!("{base}?country=ca&season=7"), url.as_str());
+
+        let transport = Arc::new(MockTransport::fixture(include_str!(
+            "../testdata/leaderboards/rm_solo_season_7.json"
+        )));
+        let client = Client::with_transport(Client::builder(), transport.clone());
+        let entries: Vec<LeaderboardEntry> = leaderboard(Leaderboard::RmSolo)
+            .with_season(7)
+            .with_client(Some(client))
+            .get(100)
+            .await
+            .expect("query should succeed")
+            .map(|entry| entry.expect("entry should be ok"))
+            .collect()
+            .await;
+        assert_eq!(10, entries.len());
+        assert!(transport
+            .requests()
+            .iter()
+            .all(|url| url.query_pairs().any(|(k, v)| k == "season" && v == "7")));
+
+        // Unknown seasons surface the API's error message, whatever the status.
+        for status in [StatusCode::BAD_REQUEST, StatusCode::OK] {
+            let transport = Arc::new(MockTransport::new(move |_| Response {
+                status,
+                body: r#"{"error": "Unknown season"}"#.into(),
+            }));
+            let client = Client::with_transport(Client::builder(), transport);
+            let err = leaderboard(Leaderboard::RmSolo)
+                .with_season(99)
+                .with_client(Some(client))
+                .get(10)
+                .await
+                .expect("query should succeed")
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>>>()
+                .expect_err("entries should fail");
+            match err.downcast_ref::<Error>() {
+                Some(Error::Api {
+                    url,
+                    status: got,
+                    message,
+                }) => {
+                    assert_eq!(status.as_u16(), *got);
+                    assert_eq!("Unknown season", message);
+                    assert!(url.as_str().starts_with(&format!("{base}?season=99")));
+                }
+                _ => panic!("unexpected error for status {status}: {err:?}"),
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_leaderboard_around() {
+        let fixture = include_str!("../testdata/leaderboards/rm_solo.json");
+        let players =
+            serde_json::from_str::<serde_json::Value>(fixture).unwrap()["players"].clone();
+        let profile_param = |url: &Url| {
+            url.query_pairs()
+                .find(|(k, _)| k == "profile_id")
+                .map(|(_, v)| v.parse::<u64>().unwrap())
+        };
+        // Responds to the profile ID filter like the API, with only that player's entry.
+        let player_page = move |url: &Url| {
+            let id = profile_param(url).unwrap();
+            let mut body: serde_json::Value = serde_json::from_str(fixture).unwrap();
+            let found: Vec<serde_json::Value> = players
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|player| player["profile_id"] == id)
+                .cloned()
+                .collect();
+            body["total_count"] = found.len().into();
+            body["count"] = found.len().into();
+            body["players"] = found.into();
+            testutils::ok(body.to_string())
+        };
+        let transport = || {
+            MockTransport::pages(fixture, "players", 50, true)
+                .with_route(move |url| profile_param(url).is_some(), player_page.clone())
+        };
+        let entries: Vec<LeaderboardEntry> = serde_json::from_str::<LeaderboardPages>(fixture)
+            .unwrap()
+            .data();
+
+        // The player ranked 30th, from the response to the profile ID filter.
+        let middle = Arc::new(
+            MockTransport::pages(fixture, "players", 50, true).with_route(
+                move |url| profile_param(url).is_some(),
+                |_| {
+                    testutils::ok(include_str!(
+                        "../testdata/leaderboards/rm_solo_profile.json"
+                    ))
+                },
+            ),
+        );
+        let cases = [
+            (middle.clone(), 8326516, 5, 25..=35),
+            (
+                Arc::new(transport()),
+                u64::from(entries[0].profile_id),
+                5,
+                1..=6,
+            ),
+            (
+                Arc::new(transport()),
+                u64::from(entries[49].profile_id),
+                5,
+                45..=50,
+            ),
+            (
+                Arc::new(transport()),
+                u64::from(entries[2].profile_id),
+                5,
+                1..=8,
+            ),
+            (
+                Arc::new(transport()),
+                u64::from(entries[29].profile_id),
+                0,
+                30..=30,
+            ),
+        ];
+        for (transport, id, radius, want) in cases {
+            let case = format!("profile {id}, radius {radius}");
+            let client = Client::with_transport(Client::builder(), transport.clone());
+            let got = leaderboard(Leaderboard::RmSolo)
+                .with_client(Some(client))
+                .around(id, radius)
+                .await
+                .expect("query should succeed");
+            assert_eq!(
+                want.clone().map(Some).collect::<Vec<_>>(),
+                got.iter().map(|entry| entry.rank).collect::<Vec<_>>(),
+                "{case}"
+            );
+            let player = got
+                .iter()
+                .find(|entry| u64::from(entry.profile_id) == id)
+                .expect("player should be included");
+            let rank = entries
+                .iter()
+                .find(|entry| u64::from(entry.profile_id) == id)
+                .and_then(|entry| entry.rank);
+            assert_eq!(rank, player.rank, "{case}");
+
+            // The rank is looked up first, then the neighbours are fetched without the filter.
+            let requests = transport.requests();
+            assert_eq!(Some(id), profile_param(&requests[0]), "{case}");
+            assert!(
+                requests[1..].iter().all(|url| profile_param(url).is_none()),
+                "{case}"
+            );
+        }
+
+        let client = Client::with_transport(Client::builder(), Arc::new(transport()));
+        assert!(leaderboard(Leaderboard::RmSolo)
+            .with_client(Some(client.clone()))
+            .around(NEPTUNE_ID, 5)
+            .await
+            .is_err());
+        assert!(leaderboard(Leaderboard::RmSolo)
+            .with_country(CountryCode::CAN)
+            .with_client(Some(client))
+            .around(entries[0].profile_id, 5)
+            .await
+            .is_err());
+
+        // The rank is looked up in the same season as the neighbours.
+        let transport = Arc::new(transport());
+        let client = Client::with_transport(Client::builder(), transport.clone());
+        leaderboard(Leaderboard::RmSolo)
+            .with_season(Some(7))
+            .with_client(Some(client))
+            .around(entries[29].profile_id, 2)
+            .await
+            .expect("query should succeed");
+        let requests = transport.requests();
+        assert!(requests.len() > 1);
+        for url in requests {
+            assert!(
+                url.query_pairs().any(|(k, v)| k == "season" && v == "7"),
+                "{url}"
+            );
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_leaderboard_rank_range() {
+        // The fixture holds ranks 1 to 50, so the mock repeats them every 50 entries.
+        let fixture = include_str!("../testdata/leaderboards/rm_solo.json");
+        let rank = |position: u32| (position - 1) % 50 + 1;
+        let cases = [
+            (
+                leaderboard(Leaderboard::RmSolo).with_rank_range(1..=10),
+                1..11,
+            ),
+            (
+                leaderboard(Leaderboard::RmSolo).with_rank_range(45..=55),
+                45..56,
+            ),
+            (
+                leaderboard(Leaderboard::RmSolo).with_rank_range(45..61),
+                45..61,
+            ),
+            (
+                leaderboard(Leaderboard::RmSolo)
+                    .with_rank_range(45..=55)
+                    .with_page_size(Some(50)),
+                45..56,
+            ),
+            (
+                leaderboard(Leaderboard::RmSolo).with_rank_range(190..),
+                190..201,
+            ),
+            (leaderboard(Leaderboard::RmSolo).with_rank_range(..=3), 1..4),
+            (
+                leaderboard(Leaderboard::RmSolo).with_rank_range(20..20),
+                20..20,
+            ),
+        ];
+        for (query, want) in cases {
+            let transport = Arc::new(MockTransport::pages(fixture, "players", 200, true));
+            let client = Client::with_transport(Client::builder(), transport.clone());
+            let ranks: Vec<Option<u32>> = query
+                .with_client(Some(client))
+                .get(1000)
+                .await
+                .expect("query should succeed")
+                .map(|entry| entry.expect("entry should be ok").rank)
+                .collect()
+                .await;
+            assert_eq!(
+                want.clone()
+                    .map(|position| Some(rank(position)))
+                    .collect::<Vec<_>>(),
+                ranks,
+                "ranks {want:?}"
+            );
+        }
+
+        // The limit still applies within the range.
+        let transport = Arc::new(MockTransport::pages(fixture, "players", 200, true));
+        let client = Client::with_transport(Client::builder(), transport);
+        let entries = leaderboard(Leaderboard::RmSolo)
+            .with_rank_range(45..=55)
+            .with_client(Some(client))
+            .get(3)
+            .await
+            .expect("query should succeed")
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(3, entries.len());
+
+        for query in [
+            leaderboard(Leaderboard::RmSolo)
+                .with_rank_range(1..=10)
+                .with_page(Some(2)),
+            leaderboard(Leaderboard::RmSolo)
+                .with_rank_range(1..=10)
+                .with_offset(Some(5)),
+            leaderboard(Leaderboard::RmSolo).with_rank_range(0..=10),
+        ] {
+            assert!(query.get(10).await.is_err());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_query_serde() {
+        let json = r#"{
+            "profile_id": 3176,
+            "leaderboard": ["rm_solo"],
+            "since": "2024-01-01T00:00:00Z",
+            "page_delay": {"secs": 1, "nanos": 0}
+        }"#;
+        let query: ProfileGamesQuery = serde_json::from_str(json).expect("query should parse");
+        assert_eq!(
+            "https://aoe4world.com/api/v0/players/3176/games?leaderboard=rm_solo&since=2024-01-01T00%3A00%3A00%2B00%3A00",
+            query.build_url().expect("url should build").as_str()
+        );
+
+        let query = global_games()
+            .with_game_kinds([GameKind::Rm1v1, GameKind::Rm2v2])
+            .with_opponent_profiles([NEPTUNE_ID, HOUSEDHORSE_ID])
+            .with_page_failure(Some(PageFailure::SkipAfterRetries(2)))
+            .with_stop_when(|game| game.ongoing == Some(true))
+            .with_client(Some(Client::default()));
+        let json = serde_json::to_string(&query).expect("query should serialize");
+        let roundtrip: GlobalGamesQuery = serde_json::from_str(&json).expect("query should parse");
+        assert_eq!(
+            query.build_url().expect("url should build"),
+            roundtrip.build_url().expect("url should build")
+        );
+        assert_eq!(json, serde_json::to_string(&roundtrip).unwrap());
+
+        let query = leaderboard(Leaderboard::RmSolo).with_rank_range(45..=55);
+        let json = serde_json::to_string(&query).expect("query should serialize");
+        let roundtrip: LeaderboardQuery = serde_json::from_str(&json).expect("query should parse");
+        assert_eq!(json, serde_json::to_string(&roundtrip).unwrap());
+    }
+
+    #[test]
+    fn test_build_url() {
+        let base = "https://aoe4world.com/api/v0";
+        let cases = [
+            (
+                profile(NEPTUNE_ID).build_url(),
+                format!("{base}/players/4635035"),
+            ),
+            (
+                profile_games(NEPTUNE_ID)
+                    .with_include_alts(Some(true))
+                    .build_url(),
+                format!("{base}/players/4635035/games?include_alts=true"),
+            ),
+            (
+                global_games()
+                    .with_leaderboard(Some(vec![GameKind::Rm1v1]))
+                    .build_url(),
+                format!("{base}/games?leaderboard=rm_1v1"),
+            ),
+            (
+                search("barbecue").with_exact(Some(true)).build_url(),
+                format!("{base}/players/search?query=barbecue&exact=true"),
+            ),
+            (
+                leaderboard(Leaderboard::RmSolo).build_url(),
+                format!("{base}/leaderboards/rm_solo"),
+            ),
+        ];
+        for (url, want) in cases {
+            assert_eq!(want, url.expect("url should build").as_str());
+        }
+
+        let urls = profiles([NEPTUNE_ID, HOUSEDHORSE_ID])
+            .build_urls()
+            .expect("urls should build");
+        let urls: Vec<_> = urls.iter().map(Url::as_str).collect();
+        assert_eq!(
+            vec![
+                format!("{base}/players/4635035"),
+                format!("{base}/players/3176")
+            ],
+            urls
+        );
+
+        // Required parameters can be set after the others.
+        assert_eq!(
+            profile_games(NEPTUNE_ID).build_url().unwrap(),
+            ProfileGamesQuery::default()
+                .with_include_alts(Some(true))
+                .with_include_alts(None)
+                .with_profile_id(NEPTUNE_ID)
+                .build_url()
+                .unwrap()
+        );
+
+        assert!(search("ab").build_url().is_err());
+        assert!(ProfilesQuery::default().build_urls().is_err());
+    }
+
+    #[test]
+    fn test_build_url_all_params() {
+        let base = "https://aoe4world.com/api/v0";
+        let since: chrono::DateTime<chrono::Utc> = "2024-01-02T03:04:05Z".parse().unwrap();
+        let cases = [
+            (
+                profile_games(NEPTUNE_ID)
+                    .with_leaderboard(Some(vec![Leaderboard::RmSolo]))
+                    .with_game_kind(Some(vec![GameKind::Qm1v1]))
+                    .with_opponent_profile_id(Some(HOUSEDHORSE_ID.into()))
+                    .with_since(Some(since))
+                    .with_updated_since(Some(since))
+                    .with_order(Some(GamesOrder::UpdatedAt))
+                    .with_include_alts(Some(true))
+                    .build_url(),
+                format!(
+                    "{base}/players/4635035/games?leaderboard=rm_solo%2Cqm_1v1\
+                     &opponent_profile_id=3176&since=2024-01-02T03%3A04%3A05%2B00%3A00\
+                     &updated_since=2024-01-02T03%3A04%3A05%2B00%3A00&order=updated_at\
+                     &include_alts=true"
+                ),
+            ),
+            (
+                global_games()
+                    .with_leaderboard(Some(vec![GameKind::Rm1v1, GameKind::Qm1v1]))
+                    .with_opponent_profile_ids(Some(vec![NEPTUNE_ID.into()]))
+                    .with_profile_ids(Some(vec![HOUSEDHORSE_ID.into()]))
+                    .with_since(Some(since))
+                    .with_order(Some(GamesOrder::StartedAt))
+                    .build_url(),
+                format!(
+                    "{base}/games?leaderboard=rm_1v1%2Cqm_1v1&opponent_profile_ids=4635035\
+                     &profile_ids=3176&since=2024-01-02T03%3A04%3A05%2B00%3A00\
+                     &order=started_at"
+                ),
+            ),
+            (
+                search("barbecue").with_exact(Some(false)).build_url(),
+                format!("{base}/players/search?query=barbecue&exact=false"),
+            ),
+            (
+                leaderboard(Leaderboard::RmSolo)
+                    .with_query(Some("beasty".to_string()))
+                    .with_profile_id(Some(NEPTUNE_ID.into()))
+                    .with_country(Some(CountryCode::CAN))
+                    .with_season(Some(7))
+                    .build_url(),
+                format!(
+                    "{base}/leaderboards/rm_solo?query=beasty&profile_id=4635035&country=ca\
+                     &season=7"
+                ),
+            ),
+            (
+                resume(Cursor {
+                    url: format!("{base}/games?leaderboard=rm_1v1").parse().unwrap(),
+                    per_page: 50,
+                    page: 3,
+                    last_key: None,
+                })
+                .build_url(),
+                format!("{base}/games?leaderboard=rm_1v1"),
+            ),
+        ];
+        for (url, want) in cases {
+            assert_eq!(want, url.expect("url should build").as_str());
+        }
+
+        assert!(ResumeQuery::default().build_url().is_err());
+        let not_games = Cursor {
+            url: format!("{base}/players/3176").parse().unwrap(),
+            per_page: 50,
+            page: 1,
+            last_key: None,
+        };
+        assert!(resume(not_games).build_url().is_err());
+    }
+
+    #[test]
+    fn test_global_games_opponent_profile_ids() {
+        let base = "https://aoe4world.com/api/v0/games";
+        let many: Vec<u64> = (1..=20).collect();
+        let cases = [
+            (vec![NEPTUNE_ID], "4635035".to_string()),
+            (
+                vec![NEPTUNE_ID, HOUSEDHORSE_ID],
+                "4635035%2C3176".to_string(),
+            ),
+            (many.clone(), itertools::join(&many, "%2C")),
+        ];
+        for (ids, want) in cases {
+            let url = global_games()
+                .with_opponent_profiles(ids.clone())
+                .build_url()
+                .expect("url should build");
+            assert_eq!(format!("{base}?opponent_profile_ids={want}"), url.as_str());
+            let ids: Vec<ProfileId> = ids.into_iter().map(Into::into).collect();
+            assert_eq!(
+                url,
+                global_games()
+                    .with_opponent_profile_ids(Some(ids))
+                    .build_url()
+                    .expect("url should build")
+            );
+        }
+
+        let both = global_games()
+            .with_opponent_profile_id(Some(NEPTUNE_ID.into()))
+            .with_opponent_profiles([HOUSEDHORSE_ID]);
+        assert!(both.build_url().is_err());
+    }
+
+    #[test]
+    fn test_profile_games_merged_leaderboards() {
+        // (leaderboards, game kinds, expected merged list, or None if rejected)
+        let cases = [
+            (vec![], vec![], Some(vec![])),
+            (vec![Leaderboard::RmSolo], vec![], Some(vec!["rm_solo"])),
+            (
+                vec![Leaderboard::Qm2v2, Leaderboard::RmTeam],
+                vec![GameKind::Qm2v2, GameKind::Qm3v3],
+                Some(vec!["qm_2v2", "rm_team", "qm_3v3"]),
+            ),
+            (
+                vec![Leaderboard::Rm2v2, Leaderboard::Rm2v2],
+                vec![GameKind::Rm2v2],
+                Some(vec!["rm_2v2"]),
+            ),
+            (
+                vec![Leaderboard::RmSolo, Leaderboard::RmTeam],
+                vec![GameKind::Rm1v1Console],
+                Some(vec!["rm_solo", "rm_team", "rm_1v1_console"]),
+            ),
+            (vec![Leaderboard::RmSolo], vec![GameKind::Rm1v1], None),
+            (vec![Leaderboard::RmTeam], vec![GameKind::Rm4v4], None),
+            (vec![Leaderboard::RmTeam, Leaderboard::Rm3v3], vec![], None),
+            (
+                vec![Leaderboard::RmTeamConsole],
+                vec![GameKind::Rm2v2Console],
+                None,
+            ),
+        ];
+        for (leaderboards, kinds, want) in cases {
+            let case = format!("leaderboards {leaderboards:?}, game kinds {kinds:?}");
+            let query = profile_games(NEPTUNE_ID)
+                .with_leaderboards(leaderboards)
+                .with_game_kinds(kinds);
+            match want {
+                Some(want) => {
+                    assert_eq!(
+                        want,
+                        query.merged_leaderboards().expect("should merge"),
+                        "{case}"
+                    );
+                    let url = query.build_url().expect("url should build");
+                    let param = url
+                        .query_pairs()
+                        .find(|(k, _)| k == "leaderboard")
+                        .map(|(_, v)| v.into_owned());
+                    let want = Some(want.join(",")).filter(|want| !want.is_empty());
+                    assert_eq!(want, param, "{case}");
+                }
+                None => {
+                    assert!(query.merged_leaderboards().is_err(), "{case}");
+                    assert!(query.build_url().is_err(), "{case}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_iterator_setters() {
+        let ids = [NEPTUNE_ID, HOUSEDHORSE_ID];
+        let cases = [
+            (
+                profile_games(NEPTUNE_ID)
+                    .with_game_kinds([GameKind::Rm1v1, GameKind::Qm2v2])
+                    .with_leaderboards(Some(Leaderboard::RmTeam))
+                    .with_opponent_profiles(ids)
+                    .build_url(),
+                profile_games(NEPTUNE_ID)
+                    .with_game_kind(Some(vec![GameKind::Rm1v1, GameKind::Qm2v2]))
+                    .with_leaderboard(Some(vec![Leaderboard::RmTeam]))
+                    .with_opponent_profile_ids(Some(ids.map(ProfileId::from).to_vec()))
+                    .build_url(),
+            ),
+            (
+                global_games()
+                    .with_game_kinds([GameKind::Rm1v1].into_iter().chain([GameKind::Rm2v2]))
+                    .with_profiles(ids.iter().copied())
+                    .build_url(),
+                global_games()
+                    .with_leaderboard(Some(vec![GameKind::Rm1v1, GameKind::Rm2v2]))
+                    .with_profile_ids(Some(ids.map(ProfileId::from).to_vec()))
+                    .build_url(),
+            ),
+        ];
+        for (got, want) in cases {
+            assert_eq!(
+                want.expect("url should build"),
+                got.expect("url should build")
+            );
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_build_url_matches_request() {
+        let transport = Arc::new(MockTransport::fixture(include_str!(
+            "../testdata/games/neptune.json"
+        )));
+        let client = Client::with_transport(Client::builder(), transport.clone());
+        let query = || {
+            profile_games(NEPTUNE_ID)
+                .with_leaderboard(Some(vec![Leaderboard::RmSolo]))
+                .with_order(Some(GamesOrder::UpdatedAt))
+                .with_client(Some(client.clone()))
+        };
+
+        let mut want = query().build_url().expect("url should build");
+        query()
+            .get(10)
+            .await
+            .expect("query should succeed")
+            .collect::<Vec<_>>()
+            .await;
+
+        want.query_pairs_mut()
+            .append_pair("limit", "10")
+            .append_pair("page", "1");
+        assert_eq!(vec![want], transport.requests());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_profile_games_include_alts() {
+        for (include_alts, want) in [
+            (None, None),
+            (Some(false), Some("false")),
+            (Some(true), Some("true")),
+        ] {
+            // Synthetic: two of neptune's games, with neptune swapped for an alt in the
+            // second, as no player with alts could be captured.
+            let transport = Arc::new(MockTransport::fixture(include_str!(
+                "../testdata/games/synthetic_alts.json"
+            )));
+            let client = Client::with_transport(Client::builder(), transport.clone());
+
+            let games: Vec<Game> = profile_games(NEPTUNE_ID)
+                .with_include_alts(include_alts)
+                .with_client(Some(client))
+                .get(100)
+                .await
+                .expect("query should succeed")
+                .map(|game| game.expect("game should be ok"))
+                .collect()
+                .await;
+
+            let requests = transport.requests();
+            let got = requests[0]
+                .query_pairs()
+                .find(|(k, _)| k == "include_alts")
+                .map(|(_, v)| v.into_owned());
+            assert_eq!(want.map(String::from), got, "{include_alts:?}");
+
+            // Games played on an alt do not include the queried profile.
+            assert_eq!(2, games.len());
+            let on_alts = games
+                .iter()
+                .filter(|game| game.player(NEPTUNE_ID).is_none())
+                .count();
+            assert_eq!(1, on_alts);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_profile_games_order() {
+        for (order, want) in [
+            (None, None),
+            (Some(GamesOrder::StartedAt), Some("started_at")),
+            (Some(GamesOrder::UpdatedAt), Some("updated_at")),
+        ] {
+            let transport = Arc::new(MockTransport::fixture(include_str!(
+                "../testdata/games/neptune.json"
+            )));
+            let client = Client::with_transport(Client::builder(), transport.clone());
+
+            profile_games(NEPTUNE_ID)
+                .with_order(order.clone())
+                .with_client(Some(client))
+                .get(1)
+                .await
+                .expect("query should succeed")
+                .collect::<Vec<_>>()
+                .await;
+
+            let requests = transport.requests();
+            let got = requests[0]
+                .query_pairs()
+                .find(|(k, _)| k == "order")
+                .map(|(_, v)| v.into_owned());
+            assert_eq!(want.map(String::from), got, "{order:?}");
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_profile_games_leaderboard_param() {
+        let transport = Arc::new(MockTransport::fixture(include_str!(
+            "../testdata/games/neptune.json"
+        )));
+        let client = Client::with_transport(Client::builder(), transport.clone());
+
+        profile_games(NEPTUNE_ID)
+            .with_leaderboard(Some(vec![Leaderboard::RmTeam, Leaderboard::Qm2v2]))
+            .with_game_kind(Some(vec![GameKind::Qm2v2, GameKind::Rm1v1]))
+            .with_client(Some(client))
+            .get(1)
+            .await
+            .expect("query should succeed")
+            .collect::<Vec<_>>()
+            .await;
+
+        let requests = transport.requests();
+        let leaderboards: Vec<_> = requests[0]
+            .query_pairs()
+            .filter(|(k, _)| k == "leaderboard")
+            .map(|(_, v)| v.into_owned())
+            .collect();
+        assert_eq!(vec!["rm_team,qm_2v2,rm_1v1"], leaderboards);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_page_delay() {
+        let delay = Duration::from_secs(1);
+        let jitter = Duration::from_millis(500);
+        // (report total, jitter, requests)
+        let cases = [(false, None, 5), (false, Some(jitter), 5), (true, None, 4)];
+        for (report_total, jitter, requests) in cases {
+            let case = format!("report total {report_total}, jitter {jitter:?}");
+            let transport = Arc::new(MockTransport::pages(
+                include_str!("../testdata/games/global.json"),
+                "games",
+                200,
+                report_total,
+            ));
+            let client = Client::with_transport(Client::builder(), transport.clone());
+
+            let start = tokio::time::Instant::now();
+            let games: Vec<_> = global_games()
+                .with_page_delay(Some(delay))
+                .with_page_delay_jitter(jitter)
+                .with_client(Some(client))
+                .get_all()
+                .await
+                .expect("query should succeed")
+                .collect()
+                .await;
+            let elapsed = start.elapsed();
+            assert_eq!(200, games.len(), "{case}");
+            assert_eq!(requests, transport.requests().len(), "{case}");
+
+            // Whether pages are fetched one after the other or together, their requests
+            // are sent the delay apart.
+            let gaps = requests as u32 - 1;
+            let min = delay * gaps + MockTransport::DELAY;
+            let max = (delay + jitter.unwrap_or_default()) * gaps + MockTransport::DELAY;
+            assert!(
+                (min..=max).contains(&elapsed),
+                "{case}: took {elapsed:?}, expected {min:?} to {max:?}"
+            );
+        }
+    }
+
     #[cfg_attr(not(feature = "test-api"), ignore)]
     #[tokio::test(flavor = "multi_thread")]
     async fn search_api_smoke() {
@@ -555,6 +4248,18 @@ mod tests {
             assert!(entry.is_ok(), "RmTeam entry {i} not ok: {entry:?}")
         }
 
+        let entries: Vec<_> = leaderboard(Leaderboard::RmSolo)
+            .with_season(7)
+            .get(100)
+            .await
+            .expect("RmSolo season 7 leaderboard")
+            .collect()
+            .await;
+        assert_eq!(100, entries.len(), "RmSolo season 7 len");
+        for (i, entry) in entries.iter().enumerate() {
+            assert!(entry.is_ok(), "RmSolo season 7 entry {i} not ok: {entry:?}")
+        }
+
         let entries: Vec<_> = leaderboard(Leaderboard::RmTeam)
             .with_country(CountryCode::CAN)
             .get(10)
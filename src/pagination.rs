@@ -2,14 +2,31 @@
 
 //! Abstractions over pagination.
 
-use std::marker::PhantomData;
+use std::{
+    collections::{HashSet, VecDeque},
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
 
 use anyhow::{bail, Result};
 use async_trait::async_trait;
 use derive_new::new;
+use futures::{
+    future::{self, Either},
+    stream::{self, BoxStream},
+    Stream, StreamExt, TryStreamExt,
+};
 use page_turner::prelude::*;
 use reqwest::Url;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+use crate::{client::Client, Error};
 
 /// Default concurrency to use when making paginated requests.
 const DEFAULT_PAGES_CONCURRENCY: usize = 8;
@@ -17,6 +34,23 @@ const DEFAULT_PAGES_CONCURRENCY: usize = 8;
 /// Default count per page to use as the limit query parameter for paginated data.
 const DEFAULT_COUNT_PER_PAGE: usize = 50;
 
+/// Maximum count per page supported by the games endpoints.
+pub(crate) const MAX_GAMES_COUNT_PER_PAGE: usize = 100;
+
+/// Maximum count per page supported by the other paginated endpoints.
+pub(crate) const MAX_COUNT_PER_PAGE: usize = 50;
+
+/// Delay before retrying a failed page, multiplied by the number of the attempt.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Number of pages skipped in a row after which a stream ends. Skipped pages cannot tell
+/// whether more follow, so without a bound a stream whose every page fails would never end.
+/// More than the pages fetched at once, so that an outage hitting them all is survived.
+const MAX_SKIPPED_PAGES: u32 = 10;
+
+/// Number of most recent item ids remembered when dropping duplicates.
+const DEDUP_CAPACITY: usize = 1000;
+
 /// Pagination info for paginated data.
 ///
 /// This is used as part of the transparent pagination streaming logic.
@@ -58,51 +92,378 @@ impl RequestAhead for PaginatedRequest {
     }
 }
 
-/// A dummy client for paginated data.
+/// Predicate deciding which items a [`PaginationClient`] yields.
+pub(crate) type Filter<U> = Arc<dyn Fn(&U) -> bool + Send + Sync>;
+
+/// Combines `filters` into one keeping the items kept by all of them, or `None` if none
+/// are set.
+pub(crate) fn all_of<U: 'static>(
+    filters: impl IntoIterator<Item = Option<Filter<U>>>,
+) -> Option<Filter<U>> {
+    let filters: Vec<Filter<U>> = filters.into_iter().flatten().collect();
+    match filters.len() {
+        0 => None,
+        1 => filters.into_iter().next(),
+        _ => Some(Arc::new(move |item: &U| filters.iter().all(|f| f(item)))),
+    }
+}
+
+/// Predicate ending a stream at the first item matching it, and whether that item is
+/// yielded.
+pub(crate) type Stop<U> = (Filter<U>, bool);
+
+/// A client for paginated data.
 pub(crate) struct PaginationClient<T, U> {
+    client: Client,
     count: usize,
+    page_size: Option<usize>,
+    max_page_size: usize,
+    start_page: Option<u32>,
+    offset: Option<u32>,
+    filter: Option<Filter<U>>,
+    stop: Option<Stop<U>>,
+    page_failure: PageFailure,
+    /// Number of pages skipped since the last one fetched.
+    skipped: AtomicU32,
+    page_delay: Duration,
+    page_delay_jitter: Duration,
+    /// Spaces out the requests for all pages, however many are fetched at once.
+    pacer: Mutex<Pacer>,
+    reverse: bool,
     _dummy1: PhantomData<T>,
     _dummy2: PhantomData<U>,
 }
 
 impl<T, U> PaginationClient<T, U> {
-    pub fn with_limit(limit: usize) -> Self {
+    pub fn new(client: Client, limit: usize) -> Self {
         Self {
+            client,
             count: limit,
+            page_size: None,
+            max_page_size: DEFAULT_COUNT_PER_PAGE,
+            start_page: None,
+            offset: None,
+            filter: None,
+            stop: None,
+            page_failure: PageFailure::default(),
+            skipped: AtomicU32::new(0),
+            page_delay: Duration::ZERO,
+            page_delay_jitter: Duration::ZERO,
+            pacer: Default::default(),
+            reverse: false,
             _dummy1: Default::default(),
             _dummy2: Default::default(),
         }
     }
+
+    /// Sets the number of items to request per page, which may be at most `max` for the
+    /// endpoint. Defaults to 50.
+    pub fn with_page_size(mut self, page_size: Option<usize>, max: usize) -> Self {
+        self.page_size = page_size;
+        self.max_page_size = max;
+        self
+    }
+
+    /// Starts pagination at `page` (counting from 1) or `offset` items in, rather than at
+    /// the first item. At most one of them may be set.
+    pub fn with_start(mut self, page: Option<u32>, offset: Option<u32>) -> Self {
+        self.start_page = page;
+        self.offset = offset;
+        self
+    }
+
+    /// Only yields items matching `filter`, for filters the server does not support. The
+    /// count then applies to matching items, so pages are turned until enough are found.
+    pub fn with_filter(mut self, filter: Option<Filter<U>>) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Drops items whose `key` was already seen, which happens when new data shifts items
+    /// onto the next page between requests. Only the last [`DEDUP_CAPACITY`] keys are
+    /// remembered. Set after any other filter, as only items passing it are remembered.
+    pub fn with_dedup(mut self, key: Option<fn(&U) -> u64>) -> Self
+    where
+        U: 'static,
+    {
+        let Some(key) = key else {
+            return self;
+        };
+        let filter = self.filter.take();
+        let seen = Mutex::new(RecentKeys::new(DEDUP_CAPACITY));
+        self.filter = Some(Arc::new(move |item: &U| {
+            filter.as_ref().is_none_or(|filter| filter(item))
+                && seen
+                    .lock()
+                    .expect("lock should not be poisoned")
+                    .insert(key(item))
+        }));
+        self
+    }
+
+    /// Ends the stream at the first item matching `predicate`, yielding that item too
+    /// unless `inclusive` is `Some(false)`. No further pages are requested, and pages
+    /// fetched ahead are dropped.
+    pub fn with_stop_when(mut self, predicate: Option<Filter<U>>, inclusive: Option<bool>) -> Self {
+        self.stop = predicate.map(|predicate| (predicate, inclusive.unwrap_or(true)));
+        self
+    }
+
+    /// Spaces the requests for pages at least `delay` apart, plus up to `jitter`, including
+    /// those for pages fetched concurrently.
+    pub fn with_page_delay(mut self, delay: Option<Duration>, jitter: Option<Duration>) -> Self {
+        self.page_delay = delay.unwrap_or_default();
+        self.page_delay_jitter = jitter.unwrap_or_default();
+        self
+    }
+
+    /// Waits until the next request may be sent. The first is sent at once, and each one
+    /// after it the page delay after the one before.
+    async fn pace(&self) {
+        if self.page_delay.is_zero() && self.page_delay_jitter.is_zero() {
+            return;
+        }
+        let at = {
+            let mut pacer = self.pacer.lock().expect("lock should not be poisoned");
+            let now = Instant::now();
+            let at = pacer.next.map_or(now, |next| next.max(now));
+            let jitter = self.page_delay_jitter.mul_f64(pacer.spread());
+            pacer.next = Some(at + self.page_delay + jitter);
+            at
+        };
+        tokio::time::sleep_until(at).await;
+    }
+
+    /// Yields items in the reverse of the API's order. The API cannot do this itself, so
+    /// pages are fetched from the last one back, which needs the server to report a total
+    /// count. Only items streams support it: streams of pages, with meta or resumable
+    /// return an error.
+    pub fn with_reverse(mut self, reverse: bool) -> Self {
+        self.reverse = reverse;
+        self
+    }
+
+    /// Sets what to do when a page cannot be fetched.
+    pub fn with_page_failure(mut self, page_failure: Option<PageFailure>) -> Self {
+        self.page_failure = page_failure.unwrap_or_default();
+        self
+    }
+
+    /// Returns whether an item passes the filter. Errors always do.
+    fn keep(filter: &Option<Filter<U>>, item: &Result<U>) -> bool {
+        match (filter, item) {
+            (Some(filter), Ok(item)) => filter(item),
+            _ => true,
+        }
+    }
+
+    /// Number of items to request per page.
+    fn per_page(&self) -> usize {
+        let per_page = self.page_size.unwrap_or(DEFAULT_COUNT_PER_PAGE);
+        match self.count {
+            // Pages must be full sized for the start page to mean the same thing regardless
+            // of the count. Still request full pages when only the pagination info is wanted.
+            _ if self.start_page.is_some() => per_page,
+            // Filtered out items don't count, so there is no telling how many are needed.
+            _ if self.filter.is_some() => per_page,
+            0 => per_page,
+            count => per_page.min(count),
+        }
+    }
+
+    /// Returns the page of the first request, and the number of its items to skip.
+    fn start(&self) -> (u32, usize) {
+        let per_page = self.per_page();
+        match (self.start_page, self.offset) {
+            (Some(page), _) => (page, 0),
+            (None, Some(offset)) => {
+                let offset = offset as usize;
+                ((offset / per_page) as u32 + 1, offset % per_page)
+            }
+            (None, None) => (1, 0),
+        }
+    }
 }
 
 #[async_trait]
 impl<T: Send + Sync + DeserializeOwned + Paginated<U>, U: Send + Sync> PageTurner<PaginatedRequest>
     for PaginationClient<T, U>
 {
-    type PageItem = U;
+    /// Items of a skipped page are replaced by a single error.
+    type PageItem = Result<U>;
     type PageError = anyhow::Error;
 
     async fn turn_page(
         &self,
         mut request: PaginatedRequest,
     ) -> PageTurnerOutput<Self, PaginatedRequest> {
-        request.url.query_pairs_mut().extend_pairs(&[
-            ("limit", DEFAULT_COUNT_PER_PAGE.min(self.count).to_string()),
+        let res = match (self.fetch(&request).await, self.page_failure) {
+            (Ok(res), _) => res,
+            (Err(e), PageFailure::SkipAfterRetries(retries)) => {
+                let e = e.context(format!(
+                    "skipped page {} after {retries} retries",
+                    request.page
+                ));
+                #[cfg(feature = "tracing")]
+                tracing::warn!(page = request.page, error = %e, "skipping page");
+                if self.skipped.fetch_add(1, Ordering::Relaxed) + 1 >= MAX_SKIPPED_PAGES {
+                    return Ok(TurnedPage::last(vec![Err(e)]));
+                }
+                request.page += 1;
+                return Ok(TurnedPage::next(vec![Err(e)], request));
+            }
+            (Err(e), _) => return Err(e),
+        };
+        self.skipped.store(0, Ordering::Relaxed);
+        let last = self.is_last_page(res.pagination());
+        let items = res.data().into_iter().map(Ok).collect();
+        if last {
+            Ok(TurnedPage::last(items))
+        } else {
+            request.page += 1;
+            Ok(TurnedPage::next(items, request))
+        }
+    }
+}
+
+impl<T: DeserializeOwned, U> PaginationClient<T, U> {
+    /// Fetches the page for `request` once it is its turn, retrying as allowed by the
+    /// failure policy.
+    ///
+    /// Errors from [`Error`] are not retried, as retrying would not change the outcome.
+    /// Retries back off, then wait for their turn like any other request.
+    ///
+    /// With the `tracing` feature, the attempts are wrapped in a `page` span recording the
+    /// page number.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "page", level = "debug", skip_all, fields(page = request.page))
+    )]
+    async fn fetch(&self, request: &PaginatedRequest) -> Result<T> {
+        let retries = match self.page_failure {
+            PageFailure::FailFast => 0,
+            PageFailure::SkipAfterRetries(retries) => retries,
+        };
+        let mut attempt = 0;
+        loop {
+            self.pace().await;
+            match self.client.get_json(self.page_url(request)).await {
+                Err(e) if attempt < retries && e.downcast_ref::<Error>().is_none() => {
+                    attempt += 1;
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(attempt, error = %e, "retrying page");
+                    tokio::time::sleep(RETRY_DELAY * attempt).await;
+                }
+                res => return res,
+            }
+        }
+    }
+}
+
+/// When the next page may be requested.
+#[derive(Default)]
+struct Pacer {
+    /// `None` until the first request.
+    next: Option<Instant>,
+    /// Number of requests paced so far.
+    requests: u32,
+}
+
+impl Pacer {
+    /// Returns a fraction of the jitter to add for the next request.
+    ///
+    /// Steps by the golden ratio, which spreads consecutive requests evenly over the range.
+    /// It starts from the process ID, so that several processes paging at once drift apart.
+    fn spread(&mut self) -> f64 {
+        const GOLDEN_RATIO: f64 = 0.618_033_988_749_895;
+        let step = std::process::id().wrapping_add(self.requests);
+        self.requests = self.requests.wrapping_add(1);
+        (f64::from(step) * GOLDEN_RATIO).fract()
+    }
+}
+
+/// Ends `items` at the first one matching `stop`, if any.
+///
+/// The stream is dropped without being polled again, so no further pages are requested
+/// and those fetched ahead are dropped.
+fn stop_items<U: Send + 'static>(
+    items: BoxStream<'static, Result<U>>,
+    stop: Option<Stop<U>>,
+) -> BoxStream<'static, Result<U>> {
+    let Some((predicate, inclusive)) = stop else {
+        return items;
+    };
+    stream::unfold(Some(items), move |items| {
+        let predicate = predicate.clone();
+        async move {
+            let mut items = items?;
+            let item = items.next().await?;
+            match item {
+                Ok(ref matched) if predicate(matched) => inclusive.then_some((item, None)),
+                _ => Some((item, Some(items))),
+            }
+        }
+    })
+    .boxed()
+}
+
+/// State of a stream from [`PaginationClient::into_resumable`].
+struct Resumable<U> {
+    pages: BoxStream<'static, Result<Vec<Result<U>>>>,
+    /// Items left on the current page.
+    items: VecDeque<Result<U>>,
+    /// Number of the current page.
+    page: u32,
+    /// Number of items on the current page, or 0 if it has not been fetched yet.
+    page_len: usize,
+    /// Number of items to skip from the start of the first page.
+    skip: usize,
+    /// Key of the last item seen on the first page, to skip up to.
+    last_key: Option<u64>,
+    handle: CursorHandle,
+}
+
+/// Flattens an item of a page into a single result.
+fn flatten<U>(item: Result<Result<U>>) -> Result<U> {
+    item?
+}
+
+impl<T, U> PaginationClient<T, U> {
+    /// Returns the URL of the page to fetch for `request`.
+    fn page_url(&self, request: &PaginatedRequest) -> Url {
+        let mut url = request.url.clone();
+        url.query_pairs_mut().extend_pairs(&[
+            ("limit", self.per_page().to_string()),
             ("page", request.page.to_string()),
         ]);
+        url
+    }
 
-        let res: T = reqwest::get(request.url.clone())
-            .await?
-            .error_for_status()?
-            .json()
-            .await?;
-        let pagination = res.pagination();
+    /// Is this the last page of data?
+    ///
+    /// When the server does not report a total count, a page with fewer items than were
+    /// requested is taken to be the last one.
+    fn is_last_page(&self, pagination: &Pagination) -> bool {
+        match pagination.total_count {
+            Some(total_count) => pagination.count + pagination.offset >= total_count,
+            None => (pagination.count as usize) < self.per_page(),
+        }
+    }
 
-        if pagination.count + pagination.offset < pagination.total_count.unwrap_or(u32::MAX) {
-            request.page += 1;
-            Ok(TurnedPage::next(res.data(), request))
-        } else {
-            Ok(TurnedPage::last(res.data()))
+    /// Validates the requested page size and starting point.
+    fn check_page_size(&self) -> Result<()> {
+        match self.page_size {
+            Some(0) => bail!("page size must be > 0"),
+            Some(n) if n > self.max_page_size => bail!(
+                "page size must be at most {} for this endpoint, got {n}",
+                self.max_page_size
+            ),
+            _ => {}
+        }
+        match (self.start_page, self.offset) {
+            (Some(_), Some(_)) => bail!("page and offset cannot both be set"),
+            (Some(0), _) => bail!("page must be > 0"),
+            _ => Ok(()),
         }
     }
 }
@@ -114,25 +475,1112 @@ impl<T: Send + Sync + DeserializeOwned + Paginated<U> + 'static, U: Send + Sync
     ///
     /// Number of pages is optimized by issuing a dummy query at the beginning to find out
     /// how much data we actually have.
+    ///
+    /// Pages start at the requested page or offset. With an offset, the first page may
+    /// include items before it.
+    ///
+    /// Pages are yielded in order, regardless of the order their responses arrive in or how
+    /// many times they are retried.
     pub(crate) async fn into_pages_concurrent(
         self,
-        request: PaginatedRequest,
-    ) -> Result<PagesStream<'static, U, anyhow::Error>> {
-        let per_page = DEFAULT_COUNT_PER_PAGE.min(self.count);
-        if per_page == 0 {
-            bail!("count must be > 0");
+        mut request: PaginatedRequest,
+    ) -> Result<PagesStream<'static, Result<U>, anyhow::Error>> {
+        self.check_page_size()?;
+        self.check_count()?;
+        let (page, skip) = self.start();
+        request.page = page;
+        if self.count == 0 {
+            return Ok(self.into_pages_ahead(1, Limit::Pages(0), request));
         }
         // Ceiling division to get total number of pages
-        let limit = Limit::Pages((self.count + per_page - 1) / per_page);
+        let limit = Limit::Pages((skip + self.count).div_ceil(self.per_page()));
         Ok(self.into_pages_ahead(DEFAULT_PAGES_CONCURRENCY, limit, request))
     }
+
+    /// Returns a concurrent stream of up to `count` items, starting at the requested page
+    /// or offset.
+    pub(crate) async fn into_items(
+        self,
+        request: PaginatedRequest,
+    ) -> Result<BoxStream<'static, Result<U>>> {
+        if self.reverse {
+            self.check_count()?;
+            return self.into_reversed_items(request).await;
+        }
+        if self.filter.is_some() {
+            self.check_page_size()?;
+            self.check_count()?;
+            if self.count == 0 {
+                return Ok(stream::empty().boxed());
+            }
+            let (_, items) = self.into_items_after_first_page(request).await?;
+            return Ok(items);
+        }
+        let (_, skip) = self.start();
+        let count = self.count;
+        let stop = self.stop.clone();
+        let pages = self.into_pages_concurrent(request).await?;
+        let items = pages.items().map(flatten).skip(skip).boxed();
+        Ok(stop_items(items, stop).take(count).boxed())
+    }
+
+    /// Returns a stream of pages holding up to `count` items in total, starting at the
+    /// requested page or offset.
+    ///
+    /// Pages are fetched concurrently, unless there is a filter: matching items are then
+    /// counted as pages come in, so they are fetched one at a time. Filtered out items are
+    /// dropped from their page, as are those before the offset.
+    pub(crate) async fn into_page_stream(
+        self,
+        mut request: PaginatedRequest,
+    ) -> Result<BoxStream<'static, Result<Page<U>>>> {
+        self.check_not_reversed()?;
+        self.check_page_size()?;
+        self.check_count()?;
+        if self.count == 0 {
+            return Ok(stream::empty().boxed());
+        }
+        let (first_page, skip) = self.start();
+        let count = self.count;
+        let filter = self.filter.clone();
+        let stop = self.stop.clone();
+        let pages = match filter {
+            Some(_) => {
+                request.page = first_page;
+                self.into_pages(request).boxed()
+            }
+            None => self.into_pages_concurrent(request).await?.boxed(),
+        };
+        // Stop before polling for another page once there are enough items.
+        let pages = stream::unfold(
+            (pages, count, first_page, skip),
+            move |(mut pages, remaining, number, skip)| {
+                let filter = filter.clone();
+                let stop = stop.clone();
+                async move {
+                    if remaining == 0 {
+                        return None;
+                    }
+                    let page = pages.next().await?;
+                    let page = page.and_then(|items| items.into_iter().collect::<Result<Vec<U>>>());
+                    let mut stopped = false;
+                    let page = page.map(|items| {
+                        let mut items: Vec<U> = items
+                            .into_iter()
+                            .skip(skip)
+                            .filter(|item| filter.as_ref().is_none_or(|filter| filter(item)))
+                            .take(remaining)
+                            .collect();
+                        if let Some((predicate, inclusive)) = &stop {
+                            if let Some(i) = items.iter().position(|item| predicate(item)) {
+                                items.truncate(if *inclusive { i + 1 } else { i });
+                                stopped = true;
+                            }
+                        }
+                        Page { number, items }
+                    });
+                    let remaining = match (stopped, &page) {
+                        (true, Ok(page)) if page.items.is_empty() => return None,
+                        (true, _) => 0,
+                        (false, page) => {
+                            remaining - page.as_ref().map_or(0, |page| page.items.len())
+                        }
+                    };
+                    Some((page, (pages, remaining, number + 1, 0)))
+                }
+            },
+        )
+        .boxed();
+        Ok(pages)
+    }
+
+    /// Returns a stream of every item, along with a [`CursorHandle`] tracking the position
+    /// in the stream, turning pages until the last one.
+    ///
+    /// `key` identifies items in the cursor. Items of the first page up to and including
+    /// the one with `last_key`, if found, are skipped, to resume from a [`Cursor`].
+    pub(crate) fn into_resumable(
+        self,
+        request: PaginatedRequest,
+        key: fn(&U) -> u64,
+        last_key: Option<u64>,
+    ) -> Result<(BoxStream<'static, Result<U>>, CursorHandle)> {
+        self.check_not_reversed()?;
+        self.check_page_size()?;
+        let (page, skip) = self.start();
+        let handle = CursorHandle(Arc::new(Mutex::new(Cursor {
+            url: request.url.clone(),
+            per_page: self.per_page(),
+            page,
+            last_key,
+        })));
+        let filter = self.filter.clone();
+        let stop = self.stop.clone();
+        let pages = self
+            .into_pages_ahead(
+                DEFAULT_PAGES_CONCURRENCY,
+                Limit::None,
+                PaginatedRequest { page, ..request },
+            )
+            .boxed();
+
+        let state = Resumable {
+            pages,
+            items: VecDeque::new(),
+            page,
+            page_len: 0,
+            skip,
+            last_key,
+            handle: handle.clone(),
+        };
+        let items = stream::unfold(state, move |mut state| {
+            let filter = filter.clone();
+            async move {
+                loop {
+                    if let Some(item) = state.items.pop_front() {
+                        if let Ok(ref item) = item {
+                            state.handle.update(state.page, Some(key(item)));
+                        }
+                        if !Self::keep(&filter, &item) {
+                            continue;
+                        }
+                        return Some((item, state));
+                    }
+                    // Every item of the current page has been consumed. An empty page is
+                    // past the end, so it is where to pick up once there is more data.
+                    if state.page_len > 0 {
+                        state.page += 1;
+                        state.handle.update(state.page, None);
+                    }
+                    let mut items = match state.pages.next().await? {
+                        Ok(items) => items,
+                        Err(e) => return Some((Err(e), state)),
+                    };
+                    state.page_len = items.len();
+                    items.drain(..std::mem::take(&mut state.skip).min(items.len()));
+                    if let Some(last_key) = state.last_key.take() {
+                        let seen = items
+                            .iter()
+                            .position(|item| matches!(item, Ok(item) if key(item) == last_key));
+                        if let Some(seen) = seen {
+                            items.drain(..=seen);
+                        }
+                    }
+                    state.items.extend(items);
+                }
+            }
+        });
+        Ok((stop_items(items.boxed(), stop), handle))
+    }
+
+    /// Returns a stream of every item, turning pages until the last one.
+    ///
+    /// The first page is fetched up front. If it reports a total count, the remaining pages
+    /// are fetched concurrently; otherwise they are fetched one at a time until a page
+    /// comes back short.
+    pub(crate) async fn into_all_items(
+        self,
+        request: PaginatedRequest,
+    ) -> Result<BoxStream<'static, Result<U>>> {
+        if self.reverse {
+            return self.into_reversed_items(request).await;
+        }
+        self.check_page_size()?;
+        let (_, items) = self.into_items_after_first_page(request).await?;
+        Ok(items)
+    }
+
+    /// Returns a stream of up to `count` items in reverse order, fetching pages from the
+    /// last one back.
+    ///
+    /// The first page is fetched up front to find the last one. The pages before it are
+    /// then fetched concurrently, no more than `count` needs, unless there is a filter: they
+    /// are then fetched one at a time until enough items match. Fails if the server does
+    /// not report a total count, as the last page cannot be found. `stop_when` applies in
+    /// the reversed order.
+    async fn into_reversed_items(
+        self,
+        mut request: PaginatedRequest,
+    ) -> Result<BoxStream<'static, Result<U>>> {
+        self.check_page_size()?;
+        let count = self.count;
+        if count == 0 {
+            return Ok(stream::empty().boxed());
+        }
+        let (first_page, skip) = self.start();
+        request.page = first_page;
+        let res = self.fetch(&request).await?;
+        let Some(total_count) = res.pagination().total_count else {
+            bail!("reversed order needs the total count of items, which the server did not report");
+        };
+        let mut first: Option<Vec<U>> = Some(res.data().into_iter().skip(skip).collect());
+
+        // Pages holding the items from the starting point to the end, last first.
+        let per_page = self.per_page();
+        let start = (first_page as usize - 1) * per_page + skip;
+        let total = total_count as usize;
+        let mut pages = vec![];
+        let mut items = 0;
+        for page in (first_page..=total.div_ceil(per_page) as u32).rev() {
+            if self.filter.is_none() && items >= count {
+                break;
+            }
+            let page_start = (page as usize - 1) * per_page;
+            items += total
+                .min(page_start + per_page)
+                .saturating_sub(page_start.max(start));
+            pages.push(page);
+        }
+
+        let concurrency = match self.filter {
+            Some(_) => 1,
+            None => DEFAULT_PAGES_CONCURRENCY,
+        };
+        let fail_fast = self.page_failure == PageFailure::FailFast;
+        let filter = self.filter.clone();
+        let stop = self.stop.clone();
+        let this = Arc::new(self);
+        let mut failed = false;
+        let items = stream::iter(pages)
+            .map(move |page| {
+                let this = this.clone();
+                let first = first.take_if(|_| page == first_page);
+                let request = PaginatedRequest {
+                    url: request.url.clone(),
+                    page,
+                };
+                async move {
+                    let mut items = match first {
+                        Some(items) => items,
+                        None => this.fetch(&request).await?.data(),
+                    };
+                    items.reverse();
+                    Ok::<_, anyhow::Error>(items)
+                }
+            })
+            .buffered(concurrency)
+            // Like the pages in API order, end at the first failure unless skipping pages.
+            .take_while(move |page| {
+                let more = !failed;
+                failed |= fail_fast && page.is_err();
+                future::ready(more)
+            })
+            .map_ok(|items| stream::iter(items.into_iter().map(Ok::<_, anyhow::Error>)))
+            .try_flatten()
+            .filter(move |item| future::ready(Self::keep(&filter, item)))
+            .boxed();
+        Ok(stop_items(items, stop).take(count).boxed())
+    }
+
+    /// Returns the pagination info of the first page along with a stream of up to `count`
+    /// items, starting with those of the first page.
+    pub(crate) async fn into_items_with_meta(
+        self,
+        request: PaginatedRequest,
+    ) -> Result<(PaginationMeta, BoxStream<'static, Result<U>>)> {
+        self.check_not_reversed()?;
+        self.check_page_size()?;
+        self.check_count()?;
+        let (pagination, items) = self.into_items_after_first_page(request).await?;
+        Ok((pagination.into(), items))
+    }
+
+    /// Fetches the first page up front, then returns its pagination info along with a
+    /// stream of up to `count` items.
+    ///
+    /// The remaining pages are fetched concurrently if the server reports a total count,
+    /// fetching no more than `count` needs. Otherwise they are fetched one at a time until
+    /// a page comes back short, and no page past that one is requested.
+    async fn into_items_after_first_page(
+        self,
+        mut request: PaginatedRequest,
+    ) -> Result<(Pagination, BoxStream<'static, Result<U>>)> {
+        let count = self.count;
+        let (page, skip) = self.start();
+        request.page = page;
+        let res = self.fetch(&request).await?;
+        let pagination = res.pagination().clone();
+        let first: Vec<U> = res
+            .data()
+            .into_iter()
+            .skip(skip)
+            .filter(|item| self.filter.as_ref().is_none_or(|filter| filter(item)))
+            .take(count)
+            .collect();
+        let first = stream::iter(first.into_iter().map(Ok));
+        if self.is_last_page(&pagination) || first.size_hint().0 >= count {
+            return Ok((pagination, stop_items(first.boxed(), self.stop.clone())));
+        }
+
+        // Number of pages to fetch, including the first one. Without a total count, the last
+        // page is only known once a short one comes back, so pages are fetched one at a time
+        // rather than requesting past it.
+        let pages = pagination
+            .total_count
+            .filter(|_| pagination.per_page > 0)
+            .map(|total_count| {
+                let total_pages = total_count.div_ceil(pagination.per_page);
+                let total_pages = total_pages.saturating_sub(page - 1) as usize;
+                match count != usize::MAX && self.filter.is_none() {
+                    true => total_pages.min((skip + count).div_ceil(self.per_page())),
+                    false => total_pages,
+                }
+            });
+        let request = request.next_request();
+        let filter = self.filter.clone();
+        let stop = self.stop.clone();
+        let rest = match pages {
+            Some(pages) => {
+                let limit = Limit::Pages(pages.saturating_sub(1));
+                self.into_pages_ahead(DEFAULT_PAGES_CONCURRENCY, limit, request)
+                    .items()
+                    .map(flatten)
+                    .boxed()
+            }
+            None => self.into_pages(request).items().map(flatten).boxed(),
+        };
+        let rest = rest.filter(move |item| future::ready(Self::keep(&filter, item)));
+        let items = stop_items(first.chain(rest).boxed(), stop)
+            .take(count)
+            .boxed();
+        Ok((pagination, items))
+    }
+
+    /// Checks that items are not to be reversed, for streams that cannot reverse them.
+    fn check_not_reversed(&self) -> Result<()> {
+        if self.reverse {
+            bail!("reversed order is only supported when streaming items");
+        }
+        Ok(())
+    }
+
+    /// Checks that `count` is within the [`Client`]'s limit.
+    fn check_count(&self) -> Result<()> {
+        if self.count > self.client.max_items() {
+            return Err(Error::LimitTooLarge {
+                requested: self.count,
+                max: self.client.max_items(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// What to do when a page cannot be fetched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum PageFailure {
+    /// End the stream with the error. Nothing is retried.
+    #[default]
+    FailFast,
+    /// Retry the page up to this many times, then yield a single error describing the page
+    /// in place of its items and carry on with the next page.
+    ///
+    /// A skipped page cannot tell whether more pages follow, so the stream ends once 10
+    /// pages in a row have been skipped.
+    ///
+    /// The first page is still fatal when it is fetched up front to find out how many
+    /// pages follow, as in `get_all` and `get_with_meta`, though it is retried.
+    SkipAfterRetries(u32),
+}
+
+/// Position in a paginated query, which can be serialized to resume the query later.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cursor {
+    /// URL of the query, with its filters but without pagination parameters.
+    pub url: Url,
+    /// Number of items per page.
+    pub per_page: usize,
+    /// Page of the next item, counting from 1.
+    pub page: u32,
+    /// ID of the last item returned from `page`, if any. Items of `page` up to and
+    /// including it are skipped on resume.
+    pub last_key: Option<u64>,
+}
+
+/// Handle on the [`Cursor`] of a stream, kept up to date as items are consumed.
+#[derive(Debug, Clone)]
+pub struct CursorHandle(Arc<Mutex<Cursor>>);
+
+impl CursorHandle {
+    /// Returns the cursor as of the last item consumed from the stream.
+    pub fn get(&self) -> Cursor {
+        self.0.lock().expect("lock should not be poisoned").clone()
+    }
+
+    fn update(&self, page: u32, last_key: Option<u64>) {
+        let mut cursor = self.0.lock().expect("lock should not be poisoned");
+        cursor.page = page;
+        cursor.last_key = last_key;
+    }
+}
+
+/// The most recent keys seen, up to a fixed capacity.
+struct RecentKeys {
+    capacity: usize,
+    order: VecDeque<u64>,
+    keys: HashSet<u64>,
+}
+
+impl RecentKeys {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            order: VecDeque::with_capacity(capacity),
+            keys: HashSet::with_capacity(capacity),
+        }
+    }
+
+    /// Remembers `key`, forgetting the oldest one if full. Returns whether it is new.
+    fn insert(&mut self, key: u64) -> bool {
+        if !self.keys.insert(key) {
+            return false;
+        }
+        if self.order.len() == self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.keys.remove(&oldest);
+            }
+        }
+        self.order.push_back(key);
+        true
+    }
+}
+
+/// A page of items, as returned by the server.
+///
+/// # Paging options
+///
+/// Paginated queries, such as [`crate::query::ProfileGamesQuery`], share these options:
+///
+/// - `page_size`: number of items to request per page, at most 100 for games and 50 for
+///   everything else. Defaults to 50. Larger pages take fewer requests to fetch the same
+///   number of items.
+/// - `page`: page to start from, counting from 1, with pages of `page_size` items. The
+///   limit applies from the start of the page. Pages past the end yield nothing. Cannot be
+///   combined with `offset`.
+/// - `offset`: number of items to skip before the first one returned. The limit applies
+///   from the offset. Cannot be combined with `page`.
+/// - `dedup`: drop items already returned from an earlier page, by ID. New data can shift
+///   items onto the next page between requests, so that they are returned twice. Off by
+///   default. The limit then counts unique items, and only the most recent ones are
+///   remembered.
+/// - `page_failure`: what to do when a page cannot be fetched, see [`PageFailure`].
+/// - `stop_when`: ends the stream at the first item matching a predicate. No further pages
+///   are requested once it matches, and pages fetched ahead are dropped. `stop_inclusive`
+///   sets whether that item is yielded, which it is by default.
+/// - `page_delay`: minimum time between the requests for pages, to stay clear of rate
+///   limits. It applies to pages fetched concurrently too, and retries back off before
+///   waiting for their turn. No delay by default.
+/// - `page_delay_jitter`: maximum time added to `page_delay`, varying between requests and
+///   processes so that several processes paging at once drift apart.
+/// - `cancellation`: token to cancel the query. Once cancelled, the stream ends with
+///   [`Error::Cancelled`] and requests still in flight are aborted. Dropping the stream
+///   aborts them as well.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    /// Number of the page, counting from 1.
+    pub number: u32,
+    /// Items on the page.
+    pub items: Vec<T>,
+}
+
+/// Pagination info reported by the server along with the first page of a query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaginationMeta {
+    /// Total number of items matching the query, if the server reports it.
+    pub total_count: Option<u32>,
+    /// Number of items per page.
+    pub per_page: u32,
+}
+
+impl PaginationMeta {
+    /// Total number of pages, if the server reports the total number of items.
+    pub fn total_pages(&self) -> Option<u32> {
+        match self.per_page {
+            0 => None,
+            per_page => Some(self.total_count?.div_ceil(per_page)),
+        }
+    }
+}
+
+impl From<Pagination> for PaginationMeta {
+    fn from(pagination: Pagination) -> Self {
+        Self {
+            total_count: pagination.total_count,
+            per_page: pagination.per_page,
+        }
+    }
+}
+
+/// Ends `items` with [`Error::Cancelled`] once `token` is cancelled.
+///
+/// The underlying stream is dropped on cancellation, which aborts any page requests that
+/// are still in flight.
+pub(crate) fn cancellable<U: Send + 'static>(
+    items: impl Stream<Item = Result<U>> + Send + 'static,
+    token: Option<CancellationToken>,
+) -> impl Stream<Item = Result<U>> {
+    let token = token.unwrap_or_default();
+    stream::unfold(Some((items.boxed(), token)), |state| async move {
+        let (mut items, token) = state?;
+        let item = {
+            let cancelled = token.cancelled();
+            futures::pin_mut!(cancelled);
+            match future::select(cancelled, items.next()).await {
+                Either::Left(_) => return Some((Err(Error::Cancelled.into()), None)),
+                Either::Right((item, _)) => item?,
+            }
+        };
+        Some((item, Some((items, token))))
+    })
 }
 
 #[cfg(test)]
 mod test_super {
-    use crate::testutils::test_serde_roundtrip_prop;
+    use std::{sync::Arc, time::Duration};
+
+    use crate::{
+        client::ClientBuilder,
+        testutils::{test_serde_roundtrip_prop, MockTransport},
+        types::games::{Game, GlobalGames, ProfileGames},
+    };
 
     use super::*;
 
     test_serde_roundtrip_prop!(Pagination);
+
+    fn url() -> Url {
+        "https://aoe4world.com/api/v0/games".parse().unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_limit_too_large() {
+        let transport = Arc::new(MockTransport::fixture(include_str!(
+            "../testdata/games/global.json"
+        )));
+        let client = Client::with_transport(
+            ClientBuilder::default().with_max_items(100),
+            transport.clone(),
+        );
+
+        let pages = PaginationClient::<GlobalGames, Game>::new(client.clone(), 100)
+            .into_pages_concurrent(PaginatedRequest::new(url()))
+            .await;
+        assert!(pages.is_ok(), "limit at the maximum should be allowed");
+
+        let err = PaginationClient::<GlobalGames, Game>::new(client, 101)
+            .into_pages_concurrent(PaginatedRequest::new(url()))
+            .await
+            .err()
+            .expect("limit above the maximum should fail");
+        assert_eq!(
+            Some(&Error::LimitTooLarge {
+                requested: 101,
+                max: 100
+            }),
+            err.downcast_ref::<Error>()
+        );
+        assert!(transport.requests().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cancellable() {
+        let transport = Arc::new(MockTransport::fixture(include_str!(
+            "../testdata/games/global.json"
+        )));
+        let client = Client::with_transport(ClientBuilder::default(), transport.clone());
+        let token = CancellationToken::new();
+
+        let pages = PaginationClient::<GlobalGames, Game>::new(client, 1000)
+            .into_pages_concurrent(PaginatedRequest::new(url()))
+            .await
+            .expect("query should succeed");
+        let items = cancellable(pages.items().map(flatten), Some(token.clone()));
+        futures::pin_mut!(items);
+
+        for _ in 0..DEFAULT_COUNT_PER_PAGE {
+            items
+                .next()
+                .await
+                .expect("first page should be streamed")
+                .expect("game should be ok");
+        }
+        let requested = transport.requests().len();
+        assert_eq!(DEFAULT_PAGES_CONCURRENCY, requested);
+
+        token.cancel();
+        let err = items
+            .next()
+            .await
+            .expect("cancellation should be streamed")
+            .expect_err("cancellation should be an error");
+        assert_eq!(Some(&Error::Cancelled), err.downcast_ref::<Error>());
+        assert!(items.next().await.is_none());
+        assert_eq!(requested, transport.requests().len());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_page_size() {
+        let transport = Arc::new(MockTransport::pages(
+            include_str!("../testdata/games/global.json"),
+            "games",
+            10_000,
+            false,
+        ));
+        let client = Client::with_transport(ClientBuilder::default(), transport.clone());
+
+        for (count, pages) in [(1000, 10), (250, 3), (100, 1), (30, 1)] {
+            let before = transport.requests().len();
+            PaginationClient::<GlobalGames, Game>::new(client.clone(), count)
+                .with_page_size(Some(100), MAX_GAMES_COUNT_PER_PAGE)
+                .into_pages_concurrent(PaginatedRequest::new(url()))
+                .await
+                .expect("query should succeed")
+                .items()
+                .map(flatten)
+                .collect::<Vec<_>>()
+                .await;
+
+            let requests = &transport.requests()[before..];
+            assert_eq!(pages, requests.len(), "count {count}");
+            let limit = 100.min(count).to_string();
+            for url in requests {
+                assert!(
+                    url.query_pairs().any(|(k, v)| k == "limit" && v == limit),
+                    "{url} should have limit {limit}"
+                );
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_page_delay() {
+        let delay = Duration::from_secs(1);
+        let jitter = Duration::from_millis(500);
+        for jitter in [None, Some(jitter)] {
+            let transport = Arc::new(MockTransport::pages(
+                include_str!("../testdata/games/global.json"),
+                "games",
+                500,
+                true,
+            ));
+            let client = Client::with_transport(ClientBuilder::default(), transport.clone());
+
+            PaginationClient::<GlobalGames, Game>::new(client, 500)
+                .with_page_delay(Some(delay), jitter)
+                .into_pages_concurrent(PaginatedRequest::new(url()))
+                .await
+                .expect("query should succeed")
+                .items()
+                .map(flatten)
+                .collect::<Vec<_>>()
+                .await;
+
+            // Pages after the first are fetched concurrently, but still sent one at a time.
+            let times = transport.request_times();
+            assert_eq!(10, times.len(), "jitter {jitter:?}");
+            let gaps: Vec<Duration> = times.windows(2).map(|t| t[1] - t[0]).collect();
+            let max = delay + jitter.unwrap_or_default();
+            for gap in &gaps {
+                assert!(
+                    (delay..=max).contains(gap),
+                    "jitter {jitter:?}: requests {gap:?} apart, expected {delay:?} to {max:?}"
+                );
+            }
+            if jitter.is_some() {
+                assert!(
+                    gaps.iter().any(|gap| *gap != gaps[0]),
+                    "jitter should vary: {gaps:?}"
+                );
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_page_size_invalid() {
+        let transport = Arc::new(MockTransport::fixture(include_str!(
+            "../testdata/games/global.json"
+        )));
+        let client = Client::with_transport(ClientBuilder::default(), transport.clone());
+
+        for page_size in [0, MAX_COUNT_PER_PAGE + 1] {
+            PaginationClient::<GlobalGames, Game>::new(client.clone(), 100)
+                .with_page_size(Some(page_size), MAX_COUNT_PER_PAGE)
+                .into_pages_concurrent(PaginatedRequest::new(url()))
+                .await
+                .err()
+                .expect("invalid page size should fail");
+        }
+        assert!(transport.requests().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_into_all_items() {
+        for report_total in [true, false] {
+            for (total, pages) in [(120, 3), (100, 3 - report_total as usize), (30, 1), (0, 1)] {
+                let transport = Arc::new(MockTransport::pages(
+                    include_str!("../testdata/games/neptune.json"),
+                    "games",
+                    total,
+                    report_total,
+                ));
+                let client = Client::with_transport(ClientBuilder::default(), transport.clone());
+
+                let games: Vec<_> = PaginationClient::<ProfileGames, Game>::new(client, usize::MAX)
+                    .into_all_items(PaginatedRequest::new(url()))
+                    .await
+                    .expect("query should succeed")
+                    .collect()
+                    .await;
+
+                let case = format!("total {total}, report_total {report_total}");
+                assert_eq!(total, games.len(), "{case}");
+                assert_eq!(pages, transport.requests().len(), "{case}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_of() {
+        let even: Filter<u32> = Arc::new(|n| n % 2 == 0);
+        let small: Filter<u32> = Arc::new(|n| *n < 10);
+        assert!(all_of::<u32>([None, None]).is_none());
+        let f = all_of([Some(even.clone()), None]).unwrap();
+        assert_eq!(
+            vec![0, 2, 12],
+            [0, 1, 2, 12]
+                .into_iter()
+                .filter(|n| f(n))
+                .collect::<Vec<_>>()
+        );
+        let f = all_of([Some(even), Some(small)]).unwrap();
+        assert_eq!(
+            vec![0, 2],
+            [0, 1, 2, 12]
+                .into_iter()
+                .filter(|n| f(n))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_in_order_prop() {
+        fn prop(u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<()> {
+            let fixture = include_str!("../testdata/games/neptune.json");
+            let total: usize = u.int_in_range(0..=50)?;
+            let page_size: usize = u.int_in_range(1..=10)?;
+            let pages = total.div_ceil(page_size) as u32 + 1;
+            let delays: Arc<Vec<u64>> = Arc::new(
+                (0..=pages)
+                    .map(|_| u.int_in_range(0..=500))
+                    .collect::<arbitrary::Result<_>>()?,
+            );
+            let failing = u.int_in_range(1..=pages)?;
+            let failures = u.int_in_range(0..=2)?;
+            // 0: into_items, 1: into_all_items, 2: into_page_stream.
+            let method = u.int_in_range(0..=2)?;
+
+            let want: Vec<u32> = serde_json::from_str::<ProfileGames>(fixture)
+                .unwrap()
+                .data()
+                .into_iter()
+                .take(total)
+                .map(|game| game.game_id)
+                .collect();
+
+            let transport = Arc::new(
+                MockTransport::pages(fixture, "games", total, true)
+                    .failing_page(failing, failures)
+                    .with_delay(move |url| {
+                        let page = url
+                            .query_pairs()
+                            .find(|(k, _)| k == "page")
+                            .and_then(|(_, v)| v.parse::<usize>().ok())
+                            .unwrap_or(1);
+                        Duration::from_millis(delays[page.min(delays.len() - 1)])
+                    }),
+            );
+            let client = Client::with_transport(ClientBuilder::default(), transport);
+            let client = PaginationClient::<ProfileGames, Game>::new(client, total)
+                .with_page_size(Some(page_size), MAX_GAMES_COUNT_PER_PAGE)
+                .with_page_failure(Some(PageFailure::SkipAfterRetries(2)));
+
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .start_paused(true)
+                .build()
+                .unwrap();
+            let got: Vec<u32> = runtime.block_on(async {
+                let request = PaginatedRequest::new(url());
+                let items = match method {
+                    0 => client.into_items(request).await,
+                    1 => client.into_all_items(request).await,
+                    _ => client.into_page_stream(request).await.map(|pages| {
+                        pages
+                            .map_ok(|page| stream::iter(page.items.into_iter().map(Ok)))
+                            .try_flatten()
+                            .boxed()
+                    }),
+                };
+                items
+                    .expect("query should succeed")
+                    .map(|game| game.expect("page should succeed").game_id)
+                    .collect()
+                    .await
+            });
+            assert_eq!(want, got, "page size {page_size}, method {method}");
+            Ok(())
+        }
+        arbtest::builder().run(prop);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_null_total_count() {
+        // 2.5 pages of data from an endpoint that never reports a total count.
+        let transport = || {
+            Arc::new(MockTransport::pages(
+                include_str!("../testdata/games/neptune.json"),
+                "games",
+                125,
+                false,
+            ))
+        };
+        let check = |case: &str, transport: &MockTransport, games: usize| {
+            assert_eq!(125, games, "{case}");
+            let pages: Vec<String> = transport
+                .requests()
+                .iter()
+                .filter_map(|url| url.query_pairs().find(|(k, _)| k == "page"))
+                .map(|(_, v)| v.into_owned())
+                .collect();
+            assert_eq!(vec!["1", "2", "3"], pages, "{case}");
+        };
+
+        let t = transport();
+        let client = Client::with_transport(ClientBuilder::default(), t.clone());
+        let games = PaginationClient::<ProfileGames, Game>::new(client, usize::MAX)
+            .into_all_items(PaginatedRequest::new(url()))
+            .await
+            .expect("query should succeed")
+            .count()
+            .await;
+        check("all items", &t, games);
+
+        let t = transport();
+        let client = Client::with_transport(ClientBuilder::default(), t.clone());
+        let (_, games) = PaginationClient::<ProfileGames, Game>::new(client, 1000)
+            .into_items_with_meta(PaginatedRequest::new(url()))
+            .await
+            .expect("query should succeed");
+        check("items with meta", &t, games.count().await);
+
+        let t = transport();
+        let client = Client::with_transport(ClientBuilder::default(), t.clone());
+        let games = PaginationClient::<ProfileGames, Game>::new(client, 1000)
+            .with_filter(Some(Arc::new(|_: &Game| true)))
+            .into_items(PaginatedRequest::new(url()))
+            .await
+            .expect("query should succeed")
+            .count()
+            .await;
+        check("filtered items", &t, games);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_into_items_with_meta() {
+        // (limit, available, report_total, expected items, expected requests)
+        let cases = [
+            (0, 120, true, 0, 1),
+            (30, 120, true, 30, 1),
+            (100, 120, true, 100, 2),
+            (500, 120, true, 120, 3),
+            (500, 120, false, 120, 3),
+            (100, 50, false, 50, 2),
+        ];
+        for (limit, available, report_total, items, requests) in cases {
+            let transport = Arc::new(MockTransport::pages(
+                include_str!("../testdata/games/neptune.json"),
+                "games",
+                available,
+                report_total,
+            ));
+            let client = Client::with_transport(ClientBuilder::default(), transport.clone());
+
+            let (meta, games) = PaginationClient::<ProfileGames, Game>::new(client, limit)
+                .into_items_with_meta(PaginatedRequest::new(url()))
+                .await
+                .expect("query should succeed");
+            let games: Vec<_> = games.collect().await;
+
+            let case = format!("limit {limit}, available {available}, report_total {report_total}");
+            assert_eq!(items, games.len(), "{case}");
+            assert_eq!(requests, transport.requests().len(), "{case}");
+            let per_page = if limit == 0 { 50 } else { limit.min(50) as u32 };
+            assert_eq!(per_page, meta.per_page, "{case}");
+            match report_total {
+                true => {
+                    assert_eq!(Some(available as u32), meta.total_count, "{case}");
+                    let pages = (available as u32).div_ceil(per_page);
+                    assert_eq!(Some(pages), meta.total_pages(), "{case}");
+                }
+                false => assert_eq!(None, meta.total_pages(), "{case}"),
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_start() {
+        // (page, offset, limit, expected first page requested, expected item window)
+        let cases = [
+            (Some(2), None, 10, 2, 50..60),
+            (Some(3), None, 100, 3, 100..120),
+            (Some(7), None, 10, 7, 0..0),
+            (None, Some(0), 10, 1, 0..10),
+            (None, Some(45), 10, 5, 45..55),
+            (None, Some(60), 100, 2, 60..120),
+            (None, Some(500), 10, 51, 0..0),
+        ];
+        for (page, offset, limit, first_page, window) in cases {
+            let case = format!("page {page:?}, offset {offset:?}, limit {limit}");
+            let transport = Arc::new(MockTransport::pages(
+                include_str!("../testdata/games/neptune.json"),
+                "games",
+                120,
+                true,
+            ));
+            let client = Client::with_transport(ClientBuilder::default(), transport.clone());
+            let all: Vec<Game> = serde_json::from_str::<ProfileGames>(include_str!(
+                "../testdata/games/neptune.json"
+            ))
+            .unwrap()
+            .data();
+
+            let games: Vec<Game> = PaginationClient::<ProfileGames, Game>::new(client, limit)
+                .with_start(page, offset)
+                .into_items(PaginatedRequest::new(url()))
+                .await
+                .expect("query should succeed")
+                .map(|game| game.expect("game should be ok"))
+                .collect()
+                .await;
+
+            let want: Vec<Game> = window.map(|i| all[i % all.len()].clone()).collect();
+            assert_eq!(want.len(), games.len(), "{case}");
+            assert!(want == games, "{case}: wrong items");
+            let first = &transport.requests()[0];
+            assert!(
+                first
+                    .query_pairs()
+                    .any(|(k, v)| k == "page" && v == first_page.to_string()),
+                "{case}: first request {first}"
+            );
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_start_invalid() {
+        let client = Client::with_transport(
+            ClientBuilder::default(),
+            Arc::new(MockTransport::fixture(include_str!(
+                "../testdata/games/global.json"
+            ))),
+        );
+        for (page, offset) in [(Some(0), None), (Some(1), Some(1))] {
+            PaginationClient::<GlobalGames, Game>::new(client.clone(), 10)
+                .with_start(page, offset)
+                .into_items(PaginatedRequest::new(url()))
+                .await
+                .err()
+                .expect("invalid start should fail");
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_into_page_stream() {
+        // (page size, limit, offset, expected page numbers and lengths)
+        let cases: [(_, _, _, &[(u32, usize)]); 5] = [
+            (None, 0, None, &[]),
+            (None, 120, None, &[(1, 50), (2, 50), (3, 20)]),
+            (Some(30), 100, None, &[(1, 30), (2, 30), (3, 30), (4, 10)]),
+            (None, 500, None, &[(1, 50), (2, 50), (3, 20)]),
+            (None, 60, Some(70), &[(2, 30), (3, 20)]),
+        ];
+        for (page_size, limit, offset, want) in cases {
+            let case = format!("page size {page_size:?}, limit {limit}, offset {offset:?}");
+            let transport = Arc::new(MockTransport::pages(
+                include_str!("../testdata/games/global.json"),
+                "games",
+                120,
+                true,
+            ));
+            let client = Client::with_transport(ClientBuilder::default(), transport.clone());
+
+            let pages: Vec<(u32, usize)> =
+                PaginationClient::<GlobalGames, Game>::new(client, limit)
+                    .with_page_size(page_size, MAX_GAMES_COUNT_PER_PAGE)
+                    .with_start(None, offset)
+                    .into_page_stream(PaginatedRequest::new(url()))
+                    .await
+                    .expect("query should succeed")
+                    .map(|page| page.expect("page should be ok"))
+                    .map(|page| (page.number, page.items.len()))
+                    .collect()
+                    .await;
+            assert_eq!(want, pages, "{case}");
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_into_page_stream_filter() {
+        let transport = Arc::new(MockTransport::pages(
+            include_str!("../testdata/games/global.json"),
+            "games",
+            120,
+            true,
+        ));
+        let client = Client::with_transport(ClientBuilder::default(), transport.clone());
+        let filter: Filter<Game> = Arc::new(|game: &Game| game.game_id.is_multiple_of(2));
+        let even_per_page =
+            serde_json::from_str::<GlobalGames>(include_str!("../testdata/games/global.json"))
+                .unwrap()
+                .data()
+                .iter()
+                .filter(|game| filter(game))
+                .count();
+
+        let pages: Vec<Page<Game>> =
+            PaginationClient::<GlobalGames, Game>::new(client, even_per_page + 1)
+                .with_filter(Some(filter.clone()))
+                .into_page_stream(PaginatedRequest::new(url()))
+                .await
+                .expect("query should succeed")
+                .map(|page| page.expect("page should be ok"))
+                .collect()
+                .await;
+        let lengths: Vec<usize> = pages.iter().map(|page| page.items.len()).collect();
+        assert_eq!(vec![even_per_page, 1], lengths);
+        assert!(pages
+            .iter()
+            .flat_map(|page| &page.items)
+            .all(|game| filter(game)));
+        assert_eq!(
+            2,
+            transport.requests().len(),
+            "pages should be fetched one at a time"
+        );
+    }
+
+    #[test]
+    fn test_recent_keys() {
+        let mut keys = RecentKeys::new(3);
+        assert!(keys.insert(1));
+        assert!(keys.insert(2));
+        assert!(!keys.insert(1), "1 should be remembered");
+        assert!(keys.insert(3));
+        assert!(keys.insert(4));
+        assert!(keys.insert(1), "1 should have been forgotten");
+        assert!(!keys.insert(4), "4 should be remembered");
+    }
 }
@@ -4,10 +4,25 @@
 
 #![cfg(test)]
 
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
 
+use anyhow::Result;
+use async_trait::async_trait;
 use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
 use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use tokio::time::Instant;
+use url::Url;
+
+use crate::client::{Response, Transport};
 
 macro_rules! test_serde_roundtrip_prop {
     ($t:ty) => {
@@ -73,9 +88,188 @@ where
     assert_eq!(obj, obj_de, "serialization should be idempotent");
 }
 
+type Handler = Box<dyn Fn(&Url) -> Response + Send + Sync>;
+
+/// A [`Transport`] serving canned responses, which records the requests it receives.
+///
+/// Each request takes [`MockTransport::DELAY`] to complete, so tests should pause tokio's
+/// clock to avoid actually waiting.
+pub struct MockTransport {
+    handler: Handler,
+    delay: Box<dyn Fn(&Url) -> Duration + Send + Sync>,
+    requests: Mutex<Vec<Url>>,
+    request_times: Mutex<Vec<Instant>>,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+impl MockTransport {
+    /// How long each request takes.
+    pub const DELAY: Duration = Duration::from_millis(100);
+
+    /// Returns a transport which responds to each request with `handler`.
+    pub fn new(handler: impl Fn(&Url) -> Response + Send + Sync + 'static) -> Self {
+        Self {
+            handler: Box::new(handler),
+            delay: Box::new(|_| Self::DELAY),
+            requests: Default::default(),
+            request_times: Default::default(),
+            in_flight: Default::default(),
+            max_in_flight: Default::default(),
+        }
+    }
+
+    /// Returns a transport which responds to every request with `body`.
+    pub fn fixture(body: &'static str) -> Self {
+        Self::new(move |_| ok(body))
+    }
+
+    /// Returns a transport which serves `total` items split into pages, according to the
+    /// `page` and `limit` query parameters of each request.
+    ///
+    /// Items are taken from the `key` array of the `fixture` page, repeating as needed.
+    /// `total_count` is only reported if `report_total` is set.
+    pub fn pages(
+        fixture: &'static str,
+        key: &'static str,
+        total: usize,
+        report_total: bool,
+    ) -> Self {
+        Self::shifting_pages(fixture, key, total, report_total, 0)
+    }
+
+    /// Like [`MockTransport::pages`], but as if `shift` new items were inserted at the
+    /// front before each page after the first, so that each page starts with the last
+    /// `shift` items of the previous one.
+    pub fn shifting_pages(
+        fixture: &'static str,
+        key: &'static str,
+        total: usize,
+        report_total: bool,
+        shift: usize,
+    ) -> Self {
+        let fixture: Value = serde_json::from_str(fixture).expect("fixture should be JSON");
+        Self::new(move |url| {
+            let param = |name: &str| {
+                url.query_pairs()
+                    .find(|(k, _)| k == name)
+                    .and_then(|(_, v)| v.parse::<usize>().ok())
+            };
+            let page = param("page").unwrap_or(1);
+            let limit = param("limit").unwrap_or(50);
+            let offset = (page - 1) * (limit - shift.min(limit));
+            let items = fixture[key].as_array().expect("fixture should have items");
+            let data: Vec<Value> = (offset..total.min(offset + limit))
+                .map(|i| items[i % items.len()].clone())
+                .collect();
+
+            let mut body = fixture.clone();
+            body["page"] = page.into();
+            body["per_page"] = limit.into();
+            body["count"] = data.len().into();
+            body["offset"] = offset.into();
+            body["total_count"] = match report_total {
+                true => total.into(),
+                false => Value::Null,
+            };
+            body[key] = data.into();
+            ok(body.to_string())
+        })
+    }
+
+    /// Makes each request take `delay(url)` instead of [`MockTransport::DELAY`].
+    pub fn with_delay(mut self, delay: impl Fn(&Url) -> Duration + Send + Sync + 'static) -> Self {
+        self.delay = Box::new(delay);
+        self
+    }
+
+    /// Responds to requests matching `route` with `handler` instead, and as before to
+    /// others.
+    pub fn with_route(
+        mut self,
+        route: impl Fn(&Url) -> bool + Send + Sync + 'static,
+        handler: impl Fn(&Url) -> Response + Send + Sync + 'static,
+    ) -> Self {
+        let fallback = self.handler;
+        self.handler = Box::new(move |url| match route(url) {
+            true => handler(url),
+            false => fallback(url),
+        });
+        self
+    }
+
+    /// Makes the first `times` requests for `page` fail with a server error.
+    pub fn failing_page(mut self, page: u32, times: usize) -> Self {
+        let handler = self.handler;
+        let failures = AtomicUsize::new(0);
+        self.handler = Box::new(move |url| {
+            let is_page = url
+                .query_pairs()
+                .any(|(k, v)| k == "page" && v == page.to_string());
+            if is_page && failures.fetch_add(1, Ordering::SeqCst) < times {
+                return Response {
+                    status: StatusCode::INTERNAL_SERVER_ERROR,
+                    body: "internal server error".into(),
+                };
+            }
+            handler(url)
+        });
+        self
+    }
+
+    /// Returns the URLs requested so far, in order.
+    pub fn requests(&self) -> Vec<Url> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Returns when each request so far was received, in order.
+    pub fn request_times(&self) -> Vec<Instant> {
+        self.request_times.lock().unwrap().clone()
+    }
+
+    /// Returns the maximum number of requests that were in flight at once.
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn get(&self, url: Url) -> Result<Response> {
+        self.requests.lock().unwrap().push(url.clone());
+        self.request_times.lock().unwrap().push(Instant::now());
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+        tokio::time::sleep((self.delay)(&url)).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        Ok((self.handler)(&url))
+    }
+}
+
+/// A 200 response with `body`.
+pub fn ok(body: impl Into<String>) -> Response {
+    Response {
+        status: StatusCode::OK,
+        body: body.into(),
+    }
+}
+
 pub mod arbitrary_with {
+    use std::collections::BTreeMap;
+
     use isocountry::CountryCode;
 
+    use crate::types::profile::{set_rating_history_timestamps, RatingHistoryEntry};
+
+    /// A rating history whose entries have the timestamps they would be deserialized with.
+    pub fn rating_history(
+        u: &mut arbitrary::Unstructured,
+    ) -> arbitrary::Result<BTreeMap<String, RatingHistoryEntry>> {
+        let mut history = u.arbitrary()?;
+        set_rating_history_timestamps(&mut history);
+        Ok(history)
+    }
+
     pub fn option_country(
         u: &mut arbitrary::Unstructured,
     ) -> arbitrary::Result<Option<CountryCode>> {
@@ -4,6 +4,9 @@
 
 use serde::{Deserialize, Serialize};
 
+/// Base URL of the civilization flags served by aoe4world.
+const FLAG_BASE_URL: &str = "https://data.aoe4world.com/images/flags";
+
 /// A civilization in AoEIV.
 #[derive(
     Serialize,
@@ -13,6 +16,7 @@ use serde::{Deserialize, Serialize};
     Copy,
     PartialEq,
     Eq,
+    Hash,
     strum::Display,
     strum::VariantArray,
     strum::EnumString,
@@ -40,15 +44,94 @@ pub enum Civilization {
     OrderOfTheDragon,
 }
 
+/// A release of AoEIV which introduced civilizations.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, strum::Display, strum::VariantArray,
+)]
+#[non_exhaustive]
+pub enum Dlc {
+    /// The base game, including civilizations added in free updates.
+    #[strum(serialize = "Base Game")]
+    BaseGame,
+    /// The Sultans Ascend expansion.
+    #[strum(serialize = "The Sultans Ascend")]
+    TheSultansAscend,
+}
+
+impl Civilization {
+    /// Returns the release which introduced the civilization.
+    ///
+    /// The Malians and Ottomans were added in a free update, so they count as part of the
+    /// base game.
+    pub fn dlc(&self) -> Option<Dlc> {
+        let dlc = match self {
+            Civilization::English
+            | Civilization::French
+            | Civilization::HolyRomanEmpire
+            | Civilization::Rus
+            | Civilization::Mongols
+            | Civilization::Chinese
+            | Civilization::AbbasidDynasty
+            | Civilization::DelhiSultanate
+            | Civilization::Ottomans
+            | Civilization::Malians => Dlc::BaseGame,
+            Civilization::Byzantines
+            | Civilization::Japanese
+            | Civilization::JeanneDarc
+            | Civilization::Ayyubids
+            | Civilization::ZhuXisLegacy
+            | Civilization::OrderOfTheDragon => Dlc::TheSultansAscend,
+        };
+        Some(dlc)
+    }
+
+    /// Returns the URL of the civilization's flag as served by aoe4world, e.g.
+    /// `https://data.aoe4world.com/images/flags/holy_roman_empire.png`.
+    pub fn icon_url(&self) -> Option<String> {
+        Some(format!("{FLAG_BASE_URL}/{self}.png"))
+    }
+
+    /// Is the civilization available without any expansion?
+    pub fn is_base_game(&self) -> bool {
+        self.dlc() == Some(Dlc::BaseGame)
+    }
+
+    /// Returns the civilization's name as shown in game, e.g. "Holy Roman Empire" or
+    /// "Zhu Xi's Legacy".
+    ///
+    /// Meant for presentation only; the [`std::fmt::Display`] and serde representations
+    /// match the API's.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Civilization::English => "English",
+            Civilization::French => "French",
+            Civilization::HolyRomanEmpire => "Holy Roman Empire",
+            Civilization::Rus => "Rus",
+            Civilization::Mongols => "Mongols",
+            Civilization::Chinese => "Chinese",
+            Civilization::AbbasidDynasty => "Abbasid Dynasty",
+            Civilization::DelhiSultanate => "Delhi Sultanate",
+            Civilization::Ottomans => "Ottomans",
+            Civilization::Malians => "Malians",
+            Civilization::Byzantines => "Byzantines",
+            Civilization::Japanese => "Japanese",
+            Civilization::JeanneDarc => "Jeanne d'Arc",
+            Civilization::Ayyubids => "Ayyubids",
+            Civilization::ZhuXisLegacy => "Zhu Xi's Legacy",
+            Civilization::OrderOfTheDragon => "Order of the Dragon",
+        }
+    }
+}
+
 impl PartialOrd for Civilization {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.to_string().cmp(&other.to_string()))
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Civilization {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
+        self.to_string().cmp(&other.to_string())
     }
 }
 
@@ -61,4 +144,56 @@ mod test_super {
     test_serde_roundtrip_prop!(Civilization);
 
     test_enum_to_string!(Civilization);
+
+    #[test]
+    fn test_dlc() {
+        assert_eq!(Some(Dlc::BaseGame), Civilization::English.dlc());
+        assert!(Civilization::Malians.is_base_game());
+        assert_eq!(Some(Dlc::TheSultansAscend), Civilization::Ayyubids.dlc());
+        assert!(!Civilization::ZhuXisLegacy.is_base_game());
+        assert_eq!("The Sultans Ascend", Dlc::TheSultansAscend.to_string());
+    }
+
+    #[test]
+    fn test_icon_url() {
+        assert_eq!(
+            Some("https://data.aoe4world.com/images/flags/english.png".to_string()),
+            Civilization::English.icon_url()
+        );
+        assert_eq!(
+            Some("https://data.aoe4world.com/images/flags/zhu_xis_legacy.png".to_string()),
+            Civilization::ZhuXisLegacy.icon_url()
+        );
+    }
+
+    #[test]
+    fn test_ord() {
+        // Civilizations sort by their API name.
+        assert!(Civilization::AbbasidDynasty < Civilization::English);
+        assert!(Civilization::Rus > Civilization::OrderOfTheDragon);
+        assert_eq!(
+            Some(std::cmp::Ordering::Equal),
+            Civilization::Rus.partial_cmp(&Civilization::Rus)
+        );
+    }
+
+    #[test]
+    fn test_display_name() {
+        assert_eq!(
+            "Holy Roman Empire",
+            Civilization::HolyRomanEmpire.display_name()
+        );
+        assert_eq!("Zhu Xi's Legacy", Civilization::ZhuXisLegacy.display_name());
+
+        use strum::VariantArray;
+        for civ in Civilization::VARIANTS {
+            let simplified: String = civ
+                .display_name()
+                .chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect();
+            let raw = civ.to_string().replace('_', "");
+            assert_eq!(raw, simplified.to_lowercase(), "{civ}");
+        }
+    }
 }
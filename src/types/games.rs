@@ -6,6 +6,7 @@ use std::{collections::HashMap, ops::Deref};
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use strum::VariantArray;
 
 use crate::{
     pagination::{Paginated, Pagination},
@@ -36,6 +37,81 @@ pub enum GamesOrder {
     UpdatedAt,
 }
 
+/// Direction in which games are ordered.
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    strum::VariantArray,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+#[cfg_attr(test, derive(arbitrary::Arbitrary))]
+#[cfg_attr(test, serde(deny_unknown_fields))]
+pub enum OrderDirection {
+    /// Oldest first.
+    Asc,
+    /// Newest first, as returned by the API.
+    #[default]
+    Desc,
+}
+
+/// Platform on which games are played.
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    strum::VariantArray,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+#[cfg_attr(test, derive(arbitrary::Arbitrary))]
+#[cfg_attr(test, serde(deny_unknown_fields))]
+pub enum Platform {
+    /// Games played on PC.
+    Desktop,
+    /// Games played on console.
+    Console,
+    /// Games played on any platform.
+    #[default]
+    All,
+}
+
+impl Platform {
+    /// Returns true if games of the given [`GameKind`] are played on this platform.
+    pub fn includes(&self, kind: &GameKind) -> bool {
+        match self {
+            Platform::Desktop => !kind.is_console(),
+            Platform::Console => kind.is_console(),
+            Platform::All => true,
+        }
+    }
+
+    /// Returns every [`GameKind`] played on this platform.
+    pub fn game_kinds(&self) -> Vec<GameKind> {
+        use strum::VariantArray;
+        GameKind::VARIANTS
+            .iter()
+            .filter(|kind| self.includes(kind))
+            .copied()
+            .collect()
+    }
+}
+
 /// Global games.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -87,6 +163,10 @@ impl Paginated<Game> for ProfileGames {
 }
 
 /// Information on a specific game.
+///
+/// NOTE: the aoe4world games endpoints do not expose pick/ban or veto information for
+/// civilizations or maps, so there is no way to model it here. Tournament drafts are
+/// not recorded by the API.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
 #[cfg_attr(test, derive(arbitrary::Arbitrary))]
@@ -111,7 +191,7 @@ pub struct Game {
     /// Season in which the game was played.
     pub season: Option<u32>,
     /// Server on which the game was played.
-    pub server: Option<String>,
+    pub server: Option<Server>,
     /// Patch on which the game was played.
     pub patch: Option<u32>,
     /// Average rating of the game.
@@ -134,7 +214,156 @@ pub struct Game {
     pub just_finished: Option<bool>,
     /// The teams in the game.
     #[serde(default)]
-    pub teams: Vec<Vec<PlayerWrapper>>,
+    pub teams: Vec<Team>,
+}
+
+impl Game {
+    /// How long the game lasted.
+    pub fn duration_chrono(&self) -> Option<chrono::Duration> {
+        self.duration.map(|d| chrono::Duration::seconds(d.into()))
+    }
+
+    /// Returns an iterator over every [`Player`] in the game, across all teams.
+    pub fn players(&self) -> impl Iterator<Item = &Player> {
+        self.teams.iter().flat_map(Team::players)
+    }
+
+    /// Returns the [`Player`] with the given [`ProfileId`], if they played in the game.
+    pub fn player(&self, id: impl Into<ProfileId>) -> Option<&Player> {
+        let id = id.into();
+        self.players().find(|p| p.profile_id == id)
+    }
+
+    /// Returns the [`GameState`] of the game.
+    pub fn state(&self) -> GameState {
+        if self.ongoing == Some(true) {
+            GameState::Ongoing
+        } else if self.just_finished == Some(true) {
+            GameState::JustFinished
+        } else {
+            GameState::Finished
+        }
+    }
+
+    /// Is the game still being played or waiting for its results to be decided?
+    fn is_undecided(&self) -> bool {
+        self.ongoing == Some(true) || self.just_finished == Some(true)
+    }
+
+    /// Returns the team that won the game.
+    ///
+    /// Returns `None` if the game is ongoing, has just finished and is waiting for results,
+    /// or has no winner.
+    pub fn winning_team(&self) -> Option<&Team> {
+        if self.is_undecided() {
+            return None;
+        }
+        self.teams
+            .iter()
+            .find(|team| team.result() == Some(GameResult::Win))
+    }
+
+    /// Returns the [`GameResult`] for the player with the given [`ProfileId`], or `None` if
+    /// they did not play in the game.
+    ///
+    /// Returns [`GameResult::Unknown`] if the game is ongoing or has just finished and is
+    /// waiting for results, so that an undecided game is not mistaken for a loss.
+    pub fn result_for(&self, id: impl Into<ProfileId>) -> Option<GameResult> {
+        let player = self.player(id)?;
+        if self.is_undecided() {
+            return Some(GameResult::Unknown);
+        }
+        Some(player.result.unwrap_or(GameResult::Unknown))
+    }
+}
+
+/// Server region on which a game is hosted.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, PartialEq, Eq, strum::Display, strum::EnumString,
+)]
+#[cfg_attr(test, serde(deny_unknown_fields))]
+pub enum Server {
+    #[serde(rename = "Asia (SE)")]
+    #[strum(serialize = "Asia (SE)")]
+    AsiaSoutheast,
+    #[serde(rename = "Australia")]
+    #[strum(serialize = "Australia")]
+    Australia,
+    #[serde(rename = "Brazil")]
+    #[strum(serialize = "Brazil")]
+    Brazil,
+    #[serde(rename = "Europe (W)")]
+    #[strum(serialize = "Europe (W)")]
+    EuropeWest,
+    #[serde(rename = "India")]
+    #[strum(serialize = "India")]
+    India,
+    #[serde(rename = "Korea")]
+    #[strum(serialize = "Korea")]
+    Korea,
+    #[serde(rename = "UK")]
+    #[strum(serialize = "UK")]
+    Uk,
+    #[serde(rename = "USA (E)")]
+    #[strum(serialize = "USA (E)")]
+    UsaEast,
+    #[serde(rename = "USA (W)")]
+    #[strum(serialize = "USA (W)")]
+    UsaWest,
+    #[serde(untagged)]
+    #[strum(default)]
+    Unknown(String),
+}
+
+impl VariantArray for Server {
+    const VARIANTS: &'static [Self] = &[
+        Self::AsiaSoutheast,
+        Self::Australia,
+        Self::Brazil,
+        Self::EuropeWest,
+        Self::India,
+        Self::Korea,
+        Self::Uk,
+        Self::UsaEast,
+        Self::UsaWest,
+    ];
+}
+
+#[cfg(test)]
+impl<'a> arbitrary::Arbitrary<'a> for Server {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // A known server's name would deserialize into its own variant, so unknown names
+        // are kept apart from them to round-trip.
+        if u.ratio(1, 4)? {
+            return Ok(Server::Unknown(format!(
+                "Unknown {}",
+                String::arbitrary(u)?
+            )));
+        }
+        u.choose(Self::VARIANTS).cloned()
+    }
+}
+
+impl Server {
+    /// Returns the server's region spelled out, e.g. "Western US" for `USA (W)`.
+    ///
+    /// Meant for presentation only; the [`std::fmt::Display`] and serde representations
+    /// match the API's. Unknown servers are shown as reported by the API.
+    pub fn display_name(&self) -> String {
+        let name = match self {
+            Server::AsiaSoutheast => "Southeast Asia",
+            Server::Australia => "Australia",
+            Server::Brazil => "Brazil",
+            Server::EuropeWest => "Western Europe",
+            Server::India => "India",
+            Server::Korea => "Korea",
+            Server::Uk => "United Kingdom",
+            Server::UsaEast => "Eastern US",
+            Server::UsaWest => "Western US",
+            Server::Unknown(name) => name,
+        };
+        name.to_string()
+    }
 }
 
 /// Type of game being played. Equivalent to [`Leaderboard`] but without `RmSolo` and
@@ -324,6 +553,175 @@ pub enum GameKind {
     Custom,
 }
 
+impl GameKind {
+    /// Returns the number of players on each team, e.g. 2 for a 2v2.
+    ///
+    /// Free-for-all games have teams of one. Returns 0 for custom games, whose team size
+    /// is not known from their kind.
+    pub fn team_size(&self) -> u8 {
+        if self.is_ffa() {
+            return 1;
+        }
+        let name = self.to_string();
+        let size = name.split('_').find_map(|part| {
+            let (a, b) = part.split_once('v')?;
+            (a == b).then(|| a.parse().ok())?
+        });
+        size.unwrap_or(0)
+    }
+
+    /// Is this a team game, with more than one player per team?
+    pub fn is_team_game(&self) -> bool {
+        self.team_size() > 1
+    }
+
+    /// Is this a ranked game?
+    pub fn is_ranked(&self) -> bool {
+        self.to_string().starts_with("rm_")
+    }
+
+    /// Is this a quick match game?
+    pub fn is_quick_match(&self) -> bool {
+        self.to_string().starts_with("qm_")
+    }
+
+    /// Is this an empire wars game?
+    pub fn is_empire_wars(&self) -> bool {
+        self.to_string().split('_').any(|part| part == "ew")
+    }
+
+    /// Is this a nomad game?
+    pub fn is_nomad(&self) -> bool {
+        self.to_string().split('_').any(|part| part == "nomad")
+    }
+
+    /// Is this a free-for-all game?
+    pub fn is_ffa(&self) -> bool {
+        self.to_string().split('_').any(|part| part == "ffa")
+    }
+
+    /// Is this a console game?
+    pub fn is_console(&self) -> bool {
+        matches!(
+            self,
+            GameKind::Rm1v1Console
+                | GameKind::Rm2v2Console
+                | GameKind::Rm3v3Console
+                | GameKind::Rm4v4Console
+                | GameKind::Qm1v1Console
+                | GameKind::Qm2v2Console
+                | GameKind::Qm3v3Console
+                | GameKind::Qm4v4Console
+                | GameKind::Qm1v1NomadConsole
+                | GameKind::Qm2v2NomadConsole
+                | GameKind::Qm3v3NomadConsole
+                | GameKind::Qm4v4NomadConsole
+                | GameKind::Qm1v1EwConsole
+                | GameKind::Qm2v2EwConsole
+                | GameKind::Qm3v3EwConsole
+                | GameKind::Qm4v4EwConsole
+                | GameKind::QmFfaConsole
+                | GameKind::QmFfaEwConsole
+                | GameKind::QmFfaNomadConsole
+        )
+    }
+}
+
+/// Converts a [`Leaderboard`] into the [`GameKind`] played on it.
+///
+/// Fails with the original leaderboard for [`Leaderboard::RmSolo`], [`Leaderboard::RmTeam`],
+/// [`Leaderboard::RmSoloConsole`] and [`Leaderboard::RmTeamConsole`], which aggregate games
+/// rather than corresponding to a single kind of game.
+impl TryFrom<Leaderboard> for GameKind {
+    type Error = Leaderboard;
+
+    fn try_from(leaderboard: Leaderboard) -> Result<Self, Self::Error> {
+        Ok(match leaderboard {
+            Leaderboard::Rm2v2 => GameKind::Rm2v2,
+            Leaderboard::Rm3v3 => GameKind::Rm3v3,
+            Leaderboard::Rm4v4 => GameKind::Rm4v4,
+            Leaderboard::Rm2v2Console => GameKind::Rm2v2Console,
+            Leaderboard::Rm3v3Console => GameKind::Rm3v3Console,
+            Leaderboard::Rm4v4Console => GameKind::Rm4v4Console,
+            Leaderboard::QmFfa => GameKind::QmFfa,
+            Leaderboard::Qm1v1 => GameKind::Qm1v1,
+            Leaderboard::Qm2v2 => GameKind::Qm2v2,
+            Leaderboard::Qm3v3 => GameKind::Qm3v3,
+            Leaderboard::Qm4v4 => GameKind::Qm4v4,
+            Leaderboard::Qm1v1Ew => GameKind::Qm1v1Ew,
+            Leaderboard::Qm2v2Ew => GameKind::Qm2v2Ew,
+            Leaderboard::Qm3v3Ew => GameKind::Qm3v3Ew,
+            Leaderboard::Qm4v4Ew => GameKind::Qm4v4Ew,
+            Leaderboard::QmFfaConsole => GameKind::QmFfaConsole,
+            Leaderboard::Qm1v1Console => GameKind::Qm1v1Console,
+            Leaderboard::Qm2v2Console => GameKind::Qm2v2Console,
+            Leaderboard::Qm3v3Console => GameKind::Qm3v3Console,
+            Leaderboard::Qm4v4Console => GameKind::Qm4v4Console,
+            Leaderboard::Qm1v1EwConsole => GameKind::Qm1v1EwConsole,
+            Leaderboard::Qm2v2EwConsole => GameKind::Qm2v2EwConsole,
+            Leaderboard::Qm3v3EwConsole => GameKind::Qm3v3EwConsole,
+            Leaderboard::Qm4v4EwConsole => GameKind::Qm4v4EwConsole,
+            Leaderboard::RmSolo
+            | Leaderboard::RmTeam
+            | Leaderboard::RmSoloConsole
+            | Leaderboard::RmTeamConsole => return Err(leaderboard),
+        })
+    }
+}
+
+/// Converts a [`GameKind`] into the [`Leaderboard`] it is ranked on.
+///
+/// [`GameKind::Rm1v1`] and [`GameKind::Rm1v1Console`] map to [`Leaderboard::RmSolo`] and
+/// [`Leaderboard::RmSoloConsole`]. Fails with the original kind for nomad, free-for-all empire
+/// wars, free-for-all nomad and custom games, which have no leaderboard.
+impl TryFrom<GameKind> for Leaderboard {
+    type Error = GameKind;
+
+    fn try_from(kind: GameKind) -> Result<Self, Self::Error> {
+        Ok(match kind {
+            GameKind::Rm1v1 => Leaderboard::RmSolo,
+            GameKind::Rm1v1Console => Leaderboard::RmSoloConsole,
+            GameKind::Rm2v2 => Leaderboard::Rm2v2,
+            GameKind::Rm3v3 => Leaderboard::Rm3v3,
+            GameKind::Rm4v4 => Leaderboard::Rm4v4,
+            GameKind::Rm2v2Console => Leaderboard::Rm2v2Console,
+            GameKind::Rm3v3Console => Leaderboard::Rm3v3Console,
+            GameKind::Rm4v4Console => Leaderboard::Rm4v4Console,
+            GameKind::QmFfa => Leaderboard::QmFfa,
+            GameKind::Qm1v1 => Leaderboard::Qm1v1,
+            GameKind::Qm2v2 => Leaderboard::Qm2v2,
+            GameKind::Qm3v3 => Leaderboard::Qm3v3,
+            GameKind::Qm4v4 => Leaderboard::Qm4v4,
+            GameKind::Qm1v1Ew => Leaderboard::Qm1v1Ew,
+            GameKind::Qm2v2Ew => Leaderboard::Qm2v2Ew,
+            GameKind::Qm3v3Ew => Leaderboard::Qm3v3Ew,
+            GameKind::Qm4v4Ew => Leaderboard::Qm4v4Ew,
+            GameKind::QmFfaConsole => Leaderboard::QmFfaConsole,
+            GameKind::Qm1v1Console => Leaderboard::Qm1v1Console,
+            GameKind::Qm2v2Console => Leaderboard::Qm2v2Console,
+            GameKind::Qm3v3Console => Leaderboard::Qm3v3Console,
+            GameKind::Qm4v4Console => Leaderboard::Qm4v4Console,
+            GameKind::Qm1v1EwConsole => Leaderboard::Qm1v1EwConsole,
+            GameKind::Qm2v2EwConsole => Leaderboard::Qm2v2EwConsole,
+            GameKind::Qm3v3EwConsole => Leaderboard::Qm3v3EwConsole,
+            GameKind::Qm4v4EwConsole => Leaderboard::Qm4v4EwConsole,
+            GameKind::Qm1v1Nomad
+            | GameKind::Qm2v2Nomad
+            | GameKind::Qm3v3Nomad
+            | GameKind::Qm4v4Nomad
+            | GameKind::Qm1v1NomadConsole
+            | GameKind::Qm2v2NomadConsole
+            | GameKind::Qm3v3NomadConsole
+            | GameKind::Qm4v4NomadConsole
+            | GameKind::QmFfaEw
+            | GameKind::QmFfaNomad
+            | GameKind::QmFfaEwConsole
+            | GameKind::QmFfaNomadConsole
+            | GameKind::Custom => return Err(kind),
+        })
+    }
+}
+
 /// The result of a match. Either a win or a loss.
 ///
 /// No-Result outcomes are not currently supported by the aoe4world API, but this may
@@ -355,13 +753,145 @@ pub enum GameResult {
     Win,
 }
 
+/// Whether a game is still being played, waiting for its results, or finished.
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum::VariantArray,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+#[cfg_attr(test, derive(arbitrary::Arbitrary))]
+#[cfg_attr(test, serde(deny_unknown_fields))]
+pub enum GameState {
+    /// The game is still being played.
+    Ongoing,
+    /// The game has finished, but its results have not been decided yet.
+    JustFinished,
+    /// The game has finished and its results are final.
+    Finished,
+}
+
+impl GameResult {
+    /// Is this a win?
+    pub fn is_win(&self) -> bool {
+        matches!(self, GameResult::Win)
+    }
+
+    /// Is this a loss?
+    pub fn is_loss(&self) -> bool {
+        matches!(self, GameResult::Loss)
+    }
+
+    /// Was the game decided, i.e. is this either a win or a loss?
+    pub fn is_decided(&self) -> bool {
+        self.is_win() || self.is_loss()
+    }
+}
+
+/// A team of players in a game.
+///
+/// The API wraps each player in an object with a single `player` key, which is hidden here.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[serde(from = "Vec<PlayerWrapper>", into = "Vec<PlayerWrapper>")]
+#[cfg_attr(test, derive(arbitrary::Arbitrary))]
+pub struct Team {
+    players: Vec<Player>,
+}
+
+impl Team {
+    /// Number of players on the team.
+    pub fn size(&self) -> usize {
+        self.players.len()
+    }
+
+    /// Players on the team.
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    /// Result of the game for the team, taken from its players. The team won if any of
+    /// them did, otherwise lost if any of them did.
+    ///
+    /// Returns `None` if no player has a result.
+    pub fn result(&self) -> Option<GameResult> {
+        let results: Vec<GameResult> = self.players.iter().filter_map(|p| p.result).collect();
+        [
+            GameResult::Win,
+            GameResult::Loss,
+            GameResult::NoResult,
+            GameResult::Unknown,
+        ]
+        .into_iter()
+        .find(|r| results.contains(r))
+    }
+
+    /// Average rating of the players on the team, ignoring those without one.
+    ///
+    /// Returns `None` if no player has a rating.
+    pub fn average_rating(&self) -> Option<f64> {
+        let ratings: Vec<u32> = self.players.iter().filter_map(|p| p.rating).collect();
+        if ratings.is_empty() {
+            return None;
+        }
+        Some(ratings.iter().map(|&r| f64::from(r)).sum::<f64>() / ratings.len() as f64)
+    }
+}
+
+impl From<Vec<Player>> for Team {
+    fn from(players: Vec<Player>) -> Self {
+        Self { players }
+    }
+}
+
+impl From<Team> for Vec<Player> {
+    fn from(team: Team) -> Self {
+        team.players
+    }
+}
+
+impl From<Vec<PlayerWrapper>> for Team {
+    fn from(players: Vec<PlayerWrapper>) -> Self {
+        players
+            .into_iter()
+            .map(Player::from)
+            .collect::<Vec<_>>()
+            .into()
+    }
+}
+
+impl From<Team> for Vec<PlayerWrapper> {
+    fn from(team: Team) -> Self {
+        team.players
+            .into_iter()
+            .map(|player| PlayerWrapper { player })
+            .collect()
+    }
+}
+
+impl<'a> IntoIterator for &'a Team {
+    type Item = &'a Player;
+    type IntoIter = std::slice::Iter<'a, Player>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.players.iter()
+    }
+}
+
 /// Wrapper around a Player. This is unfortunately needed due to the schema of the
 /// aoe4world API.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
 #[cfg_attr(test, derive(arbitrary::Arbitrary))]
 #[cfg_attr(test, serde(deny_unknown_fields))]
-pub struct PlayerWrapper {
+pub(crate) struct PlayerWrapper {
     pub player: Player,
 }
 
@@ -434,20 +964,36 @@ impl Player {
     pub fn profile(&self) -> ProfileQuery {
         self.profile_id.profile()
     }
+
+    /// Did the player win the game?
+    ///
+    /// Returns `None` if the player has no decided [`GameResult`].
+    pub fn won(&self) -> Option<bool> {
+        self.result
+            .filter(GameResult::is_decided)
+            .map(|result| result.is_win())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use serde_json::json;
+
     use crate::testutils::{test_enum_to_string, test_json, test_serde_roundtrip_prop};
 
     test_serde_roundtrip_prop!(GamesOrder);
+    test_serde_roundtrip_prop!(OrderDirection);
+    test_serde_roundtrip_prop!(Platform);
     test_serde_roundtrip_prop!(GlobalGames);
     test_serde_roundtrip_prop!(ProfileGames);
     test_serde_roundtrip_prop!(Game);
     test_serde_roundtrip_prop!(GameKind);
     test_serde_roundtrip_prop!(GameResult);
+    test_serde_roundtrip_prop!(GameState);
+    test_serde_roundtrip_prop!(Server);
+    test_serde_roundtrip_prop!(Team);
     test_serde_roundtrip_prop!(PlayerWrapper);
     test_serde_roundtrip_prop!(Player);
 
@@ -459,12 +1005,39 @@ mod tests {
 
     test_json!(ProfileGames, "../../testdata/games/jigly.json", jigly_games);
 
+    // Taken from the neptune games list, as no single game response has been captured
+    // yet. `game_api_smoke` checks the real one.
+    test_json!(
+        Game,
+        "../../testdata/games/neptune_game_from_list.json",
+        neptune_game
+    );
+
+    // The neptune game with its server renamed to one the crate doesn't know.
+    test_json!(
+        Game,
+        "../../testdata/games/unknown_server.json",
+        unknown_server_game
+    );
+
+    test_json!(
+        ProfileGames,
+        "../../testdata/games/synthetic_alts.json",
+        synthetic_alts_games
+    );
+
     test_json!(
         GlobalGames,
         "../../testdata/games/global.json",
         global_games
     );
 
+    test_json!(
+        GlobalGames,
+        "../../testdata/games/mixed_states.json",
+        mixed_states_games
+    );
+
     test_json!(
         GlobalGames,
         "../../testdata/games/global_2024_03_18.json",
@@ -480,7 +1053,305 @@ mod tests {
     test_enum_to_string!(GameKind);
     test_enum_to_string!(Leaderboard);
     test_enum_to_string!(GamesOrder);
+    test_enum_to_string!(OrderDirection);
     test_enum_to_string!(GameResult);
+    test_enum_to_string!(GameState);
+    test_enum_to_string!(Platform);
+    test_enum_to_string!(Server);
+
+    #[test]
+    fn test_platform_game_kinds() {
+        use strum::VariantArray;
+
+        let console = Platform::Console.game_kinds();
+        assert_eq!(19, console.len());
+        assert!(console.contains(&GameKind::Rm1v1Console));
+        assert!(console.contains(&GameKind::QmFfaNomadConsole));
+        for kind in &console {
+            assert!(
+                kind.to_string().ends_with("_console"),
+                "{kind} is not console"
+            );
+        }
+
+        let desktop = Platform::Desktop.game_kinds();
+        assert_eq!(GameKind::VARIANTS.len() - console.len(), desktop.len());
+        assert!(desktop.contains(&GameKind::Rm1v1));
+        assert!(desktop.contains(&GameKind::Custom));
+        for kind in &desktop {
+            assert!(!kind.to_string().ends_with("_console"), "{kind} is console");
+        }
+
+        assert_eq!(GameKind::VARIANTS.to_vec(), Platform::All.game_kinds());
+    }
+
+    #[test]
+    fn test_game_players() {
+        let games: ProfileGames =
+            serde_json::from_str(include_str!("../../testdata/games/jigly.json"))
+                .expect("should deserialize");
+        let game = &games.games[0];
+
+        assert_eq!(8, game.players().count());
+        let player = game.player(1523836).expect("player should be in game");
+        assert_eq!("[DeanOfBao]kit", player.name);
+        assert_eq!(Some(Civilization::Ayyubids), player.civilization);
+        assert_eq!(None, game.player(1));
+    }
+
+    #[test]
+    fn test_game_duration_chrono() {
+        let games: ProfileGames =
+            serde_json::from_str(include_str!("../../testdata/games/jigly.json"))
+                .expect("should deserialize");
+        let mut game = games.games[0].clone();
+        game.duration = Some(1404);
+        assert_eq!(
+            Some(chrono::Duration::seconds(1404)),
+            game.duration_chrono()
+        );
+        game.duration = None;
+        assert_eq!(None, game.duration_chrono());
+    }
+
+    #[test]
+    fn test_game_winner() {
+        let games: ProfileGames =
+            serde_json::from_str(include_str!("../../testdata/games/jigly.json"))
+                .expect("should deserialize");
+        let game = &games.games[0];
+
+        let winners = game.winning_team().expect("game should have a winner");
+        assert_eq!(&game.teams[1], winners);
+        assert_eq!(Some(GameResult::Loss), game.result_for(1523836));
+        assert_eq!(
+            Some(GameResult::Win),
+            game.result_for(winners.players()[0].profile_id)
+        );
+        assert_eq!(None, game.result_for(1));
+    }
+
+    #[test]
+    fn test_game_winner_undecided() {
+        let games: GlobalGames =
+            serde_json::from_str(include_str!("../../testdata/games/global.json"))
+                .expect("should deserialize");
+        let game = &games.games[0];
+        assert_eq!(Some(true), game.ongoing);
+
+        assert_eq!(None, game.winning_team());
+        assert_eq!(Some(GameResult::Unknown), game.result_for(18606040));
+
+        let mut game = games.games[0].clone();
+        game.ongoing = Some(false);
+        game.just_finished = Some(true);
+        let mut players: Vec<Player> = game.teams[0].clone().into();
+        players[0].result = Some(GameResult::Win);
+        game.teams[0] = players.into();
+        assert_eq!(None, game.winning_team());
+        assert_eq!(Some(GameResult::Unknown), game.result_for(18606040));
+    }
+
+    #[test]
+    fn test_team() {
+        let games: ProfileGames =
+            serde_json::from_str(include_str!("../../testdata/games/jigly.json"))
+                .expect("should deserialize");
+        let game = &games.games[0];
+        let (losers, winners) = (&game.teams[0], &game.teams[1]);
+
+        assert_eq!(4, losers.size());
+        assert_eq!("[DeanOfBao]kit", losers.players()[1].name);
+        assert_eq!(Some(GameResult::Loss), losers.result());
+        assert_eq!(Some(GameResult::Win), winners.result());
+        assert_eq!(Some(1437.25), losers.average_rating());
+        assert_eq!(Some(2063.5), winners.average_rating());
+
+        let mut players: Vec<Player> = losers.clone().into();
+        players[0].rating = None;
+        players[1].result = None;
+        players[1].rating = None;
+        let team = Team::from(players.clone());
+        assert_eq!(Some(GameResult::Loss), team.result());
+        assert_eq!(Some(1246.5), team.average_rating());
+
+        players[2].result = Some(GameResult::Unknown);
+        players[3].result = Some(GameResult::Win);
+        assert_eq!(Some(GameResult::Win), Team::from(players.clone()).result());
+        for player in &mut players {
+            player.result = player.result.map(|_| GameResult::NoResult);
+        }
+        players[0].result = Some(GameResult::Unknown);
+        assert_eq!(Some(GameResult::NoResult), Team::from(players).result());
+
+        assert_eq!(None, Team::default().result());
+        assert_eq!(None, Team::default().average_rating());
+    }
+
+    #[test]
+    fn test_game_state() {
+        let games: GlobalGames =
+            serde_json::from_str(include_str!("../../testdata/games/mixed_states.json")).unwrap();
+        let games = games.data();
+        for state in GameState::VARIANTS {
+            assert!(
+                games.iter().any(|game| game.state() == *state),
+                "fixture should have {state} games"
+            );
+        }
+        for game in &games {
+            match game.state() {
+                GameState::Ongoing => assert_eq!(Some(true), game.ongoing),
+                GameState::JustFinished => assert_eq!(Some(true), game.just_finished),
+                GameState::Finished => assert!(game.winning_team().is_some()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_game_result_predicates() {
+        for result in GameResult::VARIANTS {
+            let decided = matches!(result, GameResult::Win | GameResult::Loss);
+            assert_eq!(decided, result.is_decided(), "{result}");
+            assert_eq!(*result == GameResult::Win, result.is_win(), "{result}");
+            assert_eq!(*result == GameResult::Loss, result.is_loss(), "{result}");
+        }
+
+        let mut player: Player = serde_json::from_value(json!({
+            "name": "foo",
+            "profile_id": 1,
+        }))
+        .unwrap();
+        assert_eq!(None, player.won());
+        for (result, won) in [
+            (GameResult::Win, Some(true)),
+            (GameResult::Loss, Some(false)),
+            (GameResult::NoResult, None),
+            (GameResult::Unknown, None),
+        ] {
+            player.result = Some(result);
+            assert_eq!(won, player.won(), "{result}");
+        }
+    }
+
+    #[test]
+    fn test_team_serde_shape() {
+        let games: ProfileGames =
+            serde_json::from_str(include_str!("../../testdata/games/jigly.json"))
+                .expect("should deserialize");
+        let json = serde_json::to_value(&games.games[0].teams[0]).expect("should serialize");
+        assert_eq!(
+            Some(&json!(1523836)),
+            json[1].get("player").and_then(|p| p.get("profile_id"))
+        );
+    }
+
+    #[test]
+    fn test_game_server() {
+        let games: GlobalGames =
+            serde_json::from_str(include_str!("../../testdata/games/global.json"))
+                .expect("should deserialize");
+        let servers: Vec<_> = games
+            .games
+            .iter()
+            .filter_map(|g| g.server.clone())
+            .collect();
+        assert_eq!(games.games.len(), servers.len());
+        assert!(servers.contains(&Server::Uk));
+        assert_eq!("United Kingdom", Server::Uk.display_name());
+        assert_eq!("Western US", Server::UsaWest.display_name());
+        for server in Server::VARIANTS {
+            assert!(!server.display_name().is_empty());
+        }
+
+        let game: Game =
+            serde_json::from_str(include_str!("../../testdata/games/unknown_server.json"))
+                .expect("should deserialize");
+        let server = game.server.expect("server should be set");
+        assert_eq!(Server::Unknown("Mars".to_string()), server);
+        assert_eq!("Mars", server.display_name());
+        assert_eq!("Mars", server.to_string());
+    }
+
+    #[test]
+    fn test_game_kind_predicates() {
+        for kind in GameKind::VARIANTS {
+            let name = kind.to_string();
+            let custom = *kind == GameKind::Custom;
+            assert_eq!(
+                1,
+                [kind.is_ranked(), kind.is_quick_match(), custom]
+                    .iter()
+                    .filter(|&&b| b)
+                    .count(),
+                "{kind} should be exactly one of ranked, quick match or custom"
+            );
+            assert_eq!(name.ends_with("_console"), kind.is_console(), "{kind}");
+            assert_eq!(name.contains("_ew"), kind.is_empire_wars(), "{kind}");
+            assert_eq!(name.contains("_nomad"), kind.is_nomad(), "{kind}");
+            assert_eq!(name.contains("ffa"), kind.is_ffa(), "{kind}");
+            assert!(!(kind.is_nomad() && kind.is_empire_wars()), "{kind}");
+            match kind.team_size() {
+                0 => assert!(custom, "{kind} should have a team size"),
+                size => assert!(
+                    kind.is_ffa() || name.contains(&format!("{size}v{size}")),
+                    "{kind} should have teams of {size}"
+                ),
+            }
+            assert_eq!(kind.team_size() > 1, kind.is_team_game(), "{kind}");
+        }
+
+        assert_eq!(1, GameKind::Rm1v1.team_size());
+        assert_eq!(4, GameKind::Qm4v4EwConsole.team_size());
+        assert_eq!(1, GameKind::QmFfaNomad.team_size());
+        assert_eq!(0, GameKind::Custom.team_size());
+        assert!(GameKind::Qm2v2Nomad.is_team_game());
+        assert!(!GameKind::QmFfa.is_team_game());
+    }
+
+    #[test]
+    fn test_game_kind_leaderboard_conversion() {
+        for &leaderboard in Leaderboard::VARIANTS {
+            match GameKind::try_from(leaderboard) {
+                Ok(kind) => {
+                    assert_eq!(leaderboard.to_string(), kind.to_string());
+                    assert_eq!(Ok(leaderboard), Leaderboard::try_from(kind));
+                }
+                Err(e) => assert_eq!(leaderboard, e),
+            }
+        }
+        for &kind in GameKind::VARIANTS {
+            match Leaderboard::try_from(kind) {
+                Ok(leaderboard) => assert_eq!(
+                    kind.is_console(),
+                    leaderboard.to_string().ends_with("_console"),
+                    "{kind}"
+                ),
+                Err(e) => {
+                    assert_eq!(kind, e);
+                    assert!(
+                        kind.is_nomad() || kind.is_ffa() || kind == GameKind::Custom,
+                        "{kind}"
+                    );
+                }
+            }
+        }
+
+        for leaderboard in [
+            Leaderboard::RmSolo,
+            Leaderboard::RmTeam,
+            Leaderboard::RmSoloConsole,
+            Leaderboard::RmTeamConsole,
+        ] {
+            assert_eq!(Err(leaderboard), GameKind::try_from(leaderboard));
+        }
+        assert_eq!(Ok(Leaderboard::RmSolo), GameKind::Rm1v1.try_into());
+        assert_eq!(Ok(Leaderboard::QmFfa), GameKind::QmFfa.try_into());
+        assert_eq!(
+            Err::<Leaderboard, _>(GameKind::Qm2v2Nomad),
+            GameKind::Qm2v2Nomad.try_into()
+        );
+    }
 
     #[test]
     fn test_foo() {}
@@ -8,9 +8,13 @@ use isocountry::CountryCode;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::pagination::{Paginated, Pagination};
+use crate::{
+    pagination::{Paginated, Pagination},
+    query::{ProfileGamesQuery, ProfileQuery},
+};
 
 use super::{
+    games::GameKind,
     profile::{Avatars, ProfileId, Social},
     rank::League,
 };
@@ -188,6 +192,25 @@ impl Leaderboard {
     /// Alias for [`Leaderboard::RmSolo`].
     #[allow(non_upper_case_globals)]
     pub const Rm1v1: Leaderboard = Leaderboard::RmSolo;
+
+    /// Whether this leaderboard aggregates games of `kind`, i.e. it is one of
+    /// [`Leaderboard::RmSolo`], [`Leaderboard::RmTeam`] or their console equivalents, and
+    /// `kind` is one of the ranked kinds it rates. These leaderboards have no [`GameKind`]
+    /// of their own.
+    pub fn aggregates(self, kind: GameKind) -> bool {
+        match self {
+            Leaderboard::RmSolo => kind == GameKind::Rm1v1,
+            Leaderboard::RmSoloConsole => kind == GameKind::Rm1v1Console,
+            Leaderboard::RmTeam => {
+                matches!(kind, GameKind::Rm2v2 | GameKind::Rm3v3 | GameKind::Rm4v4)
+            }
+            Leaderboard::RmTeamConsole => matches!(
+                kind,
+                GameKind::Rm2v2Console | GameKind::Rm3v3Console | GameKind::Rm4v4Console
+            ),
+            _ => false,
+        }
+    }
 }
 
 /// A ranked leaderboard.
@@ -265,6 +288,18 @@ pub struct LeaderboardEntry {
     pub last_rating_change: Option<i64>,
 }
 
+impl LeaderboardEntry {
+    /// Returns a [`ProfileQuery`]. Used to get the profile of this [`LeaderboardEntry`].
+    pub fn profile(&self) -> ProfileQuery {
+        self.profile_id.profile()
+    }
+
+    /// Returns a [`ProfileGamesQuery`]. Used to get the games of this [`LeaderboardEntry`].
+    pub fn games(&self) -> ProfileGamesQuery {
+        self.profile_id.games()
+    }
+}
+
 impl Deref for LeaderboardEntry {
     type Target = ProfileId;
 
@@ -295,4 +330,75 @@ mod test_super {
         "../../testdata/leaderboards/rm_team.json",
         rm_team
     );
+
+    test_json!(
+        LeaderboardPages,
+        "../../testdata/leaderboards/rm_solo_country.json",
+        rm_solo_country
+    );
+
+    test_json!(
+        LeaderboardPages,
+        "../../testdata/leaderboards/rm_solo_profile.json",
+        rm_solo_profile
+    );
+
+    test_json!(
+        LeaderboardPages,
+        "../../testdata/leaderboards/rm_solo_season_7.json",
+        rm_solo_season_7
+    );
+
+    #[test]
+    fn test_entry_site_url() {
+        use crate::pagination::Paginated;
+
+        let pages: LeaderboardPages =
+            serde_json::from_str(include_str!("../../testdata/leaderboards/rm_solo.json"))
+                .expect("should deserialize");
+        for entry in pages.data() {
+            let field = entry
+                .site_url
+                .clone()
+                .expect("fixture should have site_url");
+            assert_eq!(field.replace("http://", "https://"), entry.site_url());
+        }
+    }
+
+    #[test]
+    fn test_entry_queries() {
+        let pages: LeaderboardPages =
+            serde_json::from_str(include_str!("../../testdata/leaderboards/rm_solo.json"))
+                .expect("should deserialize");
+        let entry = &pages.data()[0];
+        let base = format!("https://aoe4world.com/api/v0/players/{}", entry.profile_id);
+        assert_eq!(base, entry.profile().build_url().unwrap().as_str());
+        assert_eq!(
+            format!("{base}/games"),
+            entry.games().build_url().unwrap().as_str()
+        );
+    }
+
+    #[test]
+    fn test_aggregates() {
+        use strum::VariantArray;
+
+        for &kind in GameKind::VARIANTS {
+            let aggregated_by: Vec<_> = Leaderboard::VARIANTS
+                .iter()
+                .filter(|l| l.aggregates(kind))
+                .collect();
+            match kind.is_ranked() && Leaderboard::try_from(kind).is_ok() {
+                true => assert_eq!(1, aggregated_by.len(), "{kind}"),
+                false => assert!(aggregated_by.is_empty(), "{kind}"),
+            }
+            // Only leaderboards without a kind of their own aggregate others.
+            for l in aggregated_by {
+                assert!(GameKind::try_from(*l).is_err(), "{l} aggregates {kind}");
+            }
+        }
+        assert!(Leaderboard::RmTeam.aggregates(GameKind::Rm3v3));
+        assert!(!Leaderboard::RmTeam.aggregates(GameKind::Rm3v3Console));
+        assert!(!Leaderboard::Qm2v2.aggregates(GameKind::Qm2v2));
+    }
 }
@@ -5,9 +5,22 @@
 use serde::{Deserialize, Serialize};
 use strum::VariantArray;
 
+/// Base URL of the map images served by aoe4world.
+const MAP_IMAGE_BASE_URL: &str = "https://data.aoe4world.com/images/maps";
+
+/// Lowercases `name` and joins its words with hyphens, e.g. "King of the Hill" becomes
+/// "king-of-the-hill".
+fn slug(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
 /// A map in AoE4.
 #[derive(
-    Serialize, Deserialize, Debug, Clone, PartialEq, Eq, strum::Display, strum::EnumString,
+    Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, strum::Display, strum::EnumString,
 )]
 #[cfg_attr(test, derive(arbitrary::Arbitrary))]
 #[cfg_attr(test, serde(deny_unknown_fields))]
@@ -162,13 +175,13 @@ pub enum Map {
 
 impl PartialOrd for Map {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.to_string().cmp(&other.to_string()))
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Map {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
+        self.to_string().cmp(&other.to_string())
     }
 }
 
@@ -229,6 +242,18 @@ impl Map {
     #[allow(non_upper_case_globals)]
     pub const Mediterranean: Self = Self::Baltic;
 
+    /// Returns the URL of the map's minimap image as served by aoe4world, e.g.
+    /// `https://data.aoe4world.com/images/maps/hill-and-dale.png`.
+    ///
+    /// Crafted maps have no canonical image, so return `None`.
+    pub fn image_url(&self) -> Option<String> {
+        if *self == Map::CraftedMap {
+            return None;
+        }
+        let slug = slug(&self.to_string());
+        (!slug.is_empty()).then(|| format!("{MAP_IMAGE_BASE_URL}/{slug}.png"))
+    }
+
     pub fn map_type(&self) -> MapType {
         match self {
             Map::CraftedMap => MapType::Unknown,
@@ -326,4 +351,30 @@ mod test_super {
 
     test_enum_to_string!(Map);
     test_enum_to_string!(MapType);
+
+    #[test]
+    fn test_image_url() {
+        let cases = [
+            (Map::HillAndDale, Some("hill-and-dale")),
+            (Map::KingOfTheHill, Some("king-of-the-hill")),
+            (Map::DryArabia, Some("dry-arabia")),
+            (Map::Altai, Some("altai")),
+            (Map::CraftedMap, None),
+        ];
+        for (map, slug) in cases {
+            let want = slug.map(|slug| format!("{MAP_IMAGE_BASE_URL}/{slug}.png"));
+            assert_eq!(want, map.image_url(), "{map}");
+        }
+        for map in Map::VARIANTS.iter().filter(|map| **map != Map::CraftedMap) {
+            assert!(map.image_url().is_some(), "{map} should have an image");
+        }
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!("king-of-the-hill", slug("King of the Hill"));
+        assert_eq!("the-pit", slug("  The  Pit "));
+        assert_eq!("mountain-pass-v2", slug("Mountain Pass (v2)"));
+        assert_eq!("", slug("!!"));
+    }
 }
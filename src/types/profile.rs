@@ -8,21 +8,26 @@ use serde_json::Value;
 use std::{
     collections::{BTreeMap, HashMap},
     fmt::Display,
+    num::ParseIntError,
     ops::Deref,
+    str::FromStr,
 };
 
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     profile, profile_games,
     query::{ProfileGamesQuery, ProfileQuery},
-    types::rank::League,
+    types::{games::Game, leaderboards::Leaderboard, rank::League},
 };
 
 use super::civilization::Civilization;
 
+const PLAYER_BASE_URL: &str = "https://aoe4world.com/players";
+
 /// Player profile ID on aoe4world.
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy, Hash)]
 #[serde(rename_all = "snake_case")]
 #[cfg_attr(test, derive(arbitrary::Arbitrary))]
 #[cfg_attr(test, serde(deny_unknown_fields))]
@@ -64,6 +69,23 @@ impl From<&ProfileId> for u64 {
     }
 }
 
+impl FromStr for ProfileId {
+    type Err = ParseIntError;
+
+    /// Parses a profile ID from its decimal form, e.g. `"4635035"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(ProfileId)
+    }
+}
+
+impl TryFrom<&str> for ProfileId {
+    type Error = ParseIntError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
 impl ProfileId {
     /// Returns a [`ProfileQuery`]. Used to get profile for a player.
     pub fn profile(&self) -> ProfileQuery {
@@ -74,6 +96,20 @@ impl ProfileId {
     pub fn games(&self) -> ProfileGamesQuery {
         profile_games(self.0)
     }
+
+    /// Get the most recent game played by this [`ProfileId`], if any. Shortcut for
+    /// [`ProfileGamesQuery::latest`].
+    pub async fn latest_game(&self) -> Result<Option<Game>> {
+        self.games().latest().await
+    }
+
+    /// Returns the URL of the player's page on aoe4world, e.g.
+    /// `https://aoe4world.com/players/3176`.
+    ///
+    /// Unlike [`Profile::site_url`], which the API may omit, this is always available.
+    pub fn site_url(&self) -> String {
+        format!("{PLAYER_BASE_URL}/{self}")
+    }
 }
 
 /// Player profile and statistics.
@@ -104,6 +140,14 @@ pub struct Profile {
     pub last_game_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+impl Profile {
+    /// Returns the player's stats on `leaderboard`, if they have any. Shortcut for
+    /// [`GameModes::get`].
+    pub fn mode(&self, leaderboard: Leaderboard) -> Option<&GameModeStats> {
+        self.modes.as_ref()?.get(leaderboard)
+    }
+}
+
 impl Deref for Profile {
     type Target = ProfileId;
 
@@ -190,6 +234,107 @@ pub struct GameModes {
     pub custom: Option<GameModeStats>,
 }
 
+impl GameModes {
+    /// Returns an iterator over the modes with stats, as pairs of the mode's name (as used
+    /// by the API) and its stats.
+    ///
+    /// Includes the deprecated `rm_1v1` mode, which duplicates `rm_solo`.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &GameModeStats)> {
+        #[allow(deprecated)]
+        let modes = [
+            ("rm_solo", &self.rm_solo),
+            ("rm_team", &self.rm_team),
+            ("rm_1v1", &self.rm_1v1),
+            ("rm_1v1_elo", &self.rm_1v1_elo),
+            ("rm_2v2_elo", &self.rm_2v2_elo),
+            ("rm_3v3_elo", &self.rm_3v3_elo),
+            ("rm_4v4_elo", &self.rm_4v4_elo),
+            ("qm_1v1", &self.qm_1v1),
+            ("qm_2v2", &self.qm_2v2),
+            ("qm_3v3", &self.qm_3v3),
+            ("qm_4v4", &self.qm_4v4),
+            ("qm_1v1_ew", &self.qm_1v1_ew),
+            ("qm_2v2_ew", &self.qm_2v2_ew),
+            ("qm_3v3_ew", &self.qm_3v3_ew),
+            ("qm_4v4_ew", &self.qm_4v4_ew),
+            ("custom", &self.custom),
+        ];
+        modes
+            .into_iter()
+            .filter_map(|(name, stats)| Some((name, stats.as_ref()?)))
+    }
+
+    /// Returns the stats for `leaderboard`, if the player has any.
+    ///
+    /// Leaderboards without a mode of their own, such as free-for-all and console ones,
+    /// always return `None`.
+    pub fn get(&self, leaderboard: Leaderboard) -> Option<&GameModeStats> {
+        let stats = match leaderboard {
+            Leaderboard::RmSolo => &self.rm_solo,
+            Leaderboard::RmTeam => &self.rm_team,
+            Leaderboard::Rm2v2 => &self.rm_2v2_elo,
+            Leaderboard::Rm3v3 => &self.rm_3v3_elo,
+            Leaderboard::Rm4v4 => &self.rm_4v4_elo,
+            Leaderboard::Qm1v1 => &self.qm_1v1,
+            Leaderboard::Qm2v2 => &self.qm_2v2,
+            Leaderboard::Qm3v3 => &self.qm_3v3,
+            Leaderboard::Qm4v4 => &self.qm_4v4,
+            Leaderboard::Qm1v1Ew => &self.qm_1v1_ew,
+            Leaderboard::Qm2v2Ew => &self.qm_2v2_ew,
+            Leaderboard::Qm3v3Ew => &self.qm_3v3_ew,
+            Leaderboard::Qm4v4Ew => &self.qm_4v4_ew,
+            _ => return None,
+        };
+        stats.as_ref()
+    }
+
+    /// Returns the mode with the highest rating, as a pair of the mode's name (as used by
+    /// the API) and its stats. Ties go to the mode listed first in [`GameModes::iter`].
+    ///
+    /// The deprecated `rm_1v1` mode is skipped, as are modes without a rating. Note that
+    /// ranked points (`rm_solo`, `rm_team`) and ELO (every other mode) are compared as is.
+    pub fn best(&self) -> Option<(&'static str, &GameModeStats)> {
+        self.current_modes()
+            .filter(|(_, stats)| stats.rating.is_some())
+            .reduce(|best, mode| match mode.1.rating > best.1.rating {
+                true => mode,
+                false => best,
+            })
+    }
+
+    /// Total number of games played across every mode.
+    ///
+    /// The deprecated `rm_1v1` mode is skipped so that games are not counted twice.
+    pub fn total_games(&self) -> u32 {
+        self.current_modes()
+            .filter_map(|(_, stats)| stats.games_count)
+            .sum()
+    }
+
+    /// Total number of games won across every mode.
+    ///
+    /// The deprecated `rm_1v1` mode is skipped so that games are not counted twice.
+    pub fn total_wins(&self) -> u32 {
+        self.current_modes()
+            .filter_map(|(_, stats)| stats.wins_count)
+            .sum()
+    }
+
+    /// Win rate across every mode as a percentage out of 100, or `None` if no games were
+    /// played.
+    pub fn overall_win_rate(&self) -> Option<f64> {
+        match self.total_games() {
+            0 => None,
+            games => Some(100.0 * self.total_wins() as f64 / games as f64),
+        }
+    }
+
+    /// Returns an iterator over the modes with stats, skipping the deprecated `rm_1v1`.
+    fn current_modes(&self) -> impl Iterator<Item = (&'static str, &GameModeStats)> {
+        self.iter().filter(|(name, _)| *name != "rm_1v1")
+    }
+}
+
 /// Statistics for a game mode.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -228,8 +373,10 @@ pub struct GameModeStats {
     pub win_rate: Option<f64>,
     /// The player's league and division.
     pub rank_level: Option<League>,
-    /// The player's rating history. Maps Game ID to RatingHistoryEntry.
-    #[serde(default)]
+    /// The player's rating history, keyed by the Unix timestamp of each game as a string. See
+    /// [`GameModeStats::rating_history_sorted`] for the entries in order.
+    #[serde(default, deserialize_with = "deserialize_rating_history")]
+    #[cfg_attr(test, arbitrary(with = crate::testutils::arbitrary_with::rating_history))]
     pub rating_history: BTreeMap<String, RatingHistoryEntry>,
     /// Stats per-civ.
     #[serde(default)]
@@ -242,6 +389,46 @@ pub struct GameModeStats {
     pub previous_seasons: Vec<PreviousSeasonStats>,
 }
 
+impl GameModeStats {
+    /// Returns the rating history in ascending numeric order of its keys, i.e.
+    /// chronologically.
+    ///
+    /// The keys of [`GameModeStats::rating_history`] are timestamps stored as strings, so the
+    /// map itself sorts them lexicographically ("10" before "9"). Entries whose key is not a
+    /// number are skipped.
+    pub fn rating_history_sorted(&self) -> Vec<(u64, &RatingHistoryEntry)> {
+        let mut history: Vec<_> = self
+            .rating_history
+            .iter()
+            .filter_map(|(key, entry)| Some((key.parse().ok()?, entry)))
+            .collect();
+        history.sort_by_key(|(key, _)| *key);
+        history
+    }
+}
+
+/// Deserializes a rating history, setting the timestamp of each entry from its key.
+fn deserialize_rating_history<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<String, RatingHistoryEntry>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let mut history = BTreeMap::deserialize(deserializer)?;
+    set_rating_history_timestamps(&mut history);
+    Ok(history)
+}
+
+/// Sets the timestamp of each entry in a rating history from its key.
+pub(crate) fn set_rating_history_timestamps(history: &mut BTreeMap<String, RatingHistoryEntry>) {
+    for (key, entry) in history.iter_mut() {
+        entry.timestamp = key
+            .parse()
+            .ok()
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0));
+    }
+}
+
 /// Statistics for previous season.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -295,6 +482,11 @@ pub struct RatingHistoryEntry {
     pub disputes_count: Option<u32>,
     /// This field is populated the player has decayed between this match and the previous one. It contains the original rating after the decay but before the match was played.
     pub orig_rating: Option<u32>,
+    /// When the game was played. The API does not send this per entry: it is taken from the
+    /// entry's key in [`GameModeStats::rating_history`] when deserializing.
+    #[serde(skip)]
+    #[cfg_attr(test, arbitrary(value = None))]
+    pub timestamp: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 /// Per-Civilization stats.
@@ -346,6 +538,49 @@ pub struct CivGameLengthStats {
     breakdown: Vec<HashMap<String, Value>>,
 }
 
+impl CivGameLengthStats {
+    /// Average duration.
+    pub fn average_duration(&self) -> Option<chrono::Duration> {
+        self.average.and_then(seconds_to_duration)
+    }
+
+    /// Median duration.
+    pub fn median_duration(&self) -> Option<chrono::Duration> {
+        self.median.and_then(seconds_to_duration)
+    }
+
+    /// Average duration for wins.
+    pub fn wins_average_duration(&self) -> Option<chrono::Duration> {
+        self.wins_average.and_then(seconds_to_duration)
+    }
+
+    /// Median duration for wins.
+    pub fn wins_median_duration(&self) -> Option<chrono::Duration> {
+        self.wins_median.and_then(seconds_to_duration)
+    }
+
+    /// Average duration for losses.
+    pub fn losses_average_duration(&self) -> Option<chrono::Duration> {
+        self.losses_average.and_then(seconds_to_duration)
+    }
+
+    /// Median duration for losses.
+    pub fn losses_median_duration(&self) -> Option<chrono::Duration> {
+        self.losses_median.and_then(seconds_to_duration)
+    }
+}
+
+/// Converts a number of seconds into a [`chrono::Duration`], rounded to the millisecond.
+/// Returns `None` for negative or non-finite values.
+fn seconds_to_duration(seconds: f64) -> Option<chrono::Duration> {
+    if !seconds.is_finite() || seconds < 0.0 {
+        return None;
+    }
+    Some(chrono::Duration::milliseconds(
+        (seconds * 1000.0).round() as i64
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testutils::{test_json, test_serde_roundtrip_prop};
@@ -376,4 +611,257 @@ mod tests {
     );
 
     test_json!(Profile, "../../testdata/profile/jigly.json", jigly_profile);
+
+    #[test]
+    fn test_profile_id_from_str() {
+        assert_eq!(Ok(ProfileId(4635035)), "4635035".parse());
+        assert_eq!(Ok(ProfileId(3176)), ProfileId::try_from("3176"));
+        for s in [
+            "",
+            "neptune",
+            "-1",
+            "4635035-neptune",
+            " 3176",
+            "18446744073709551616",
+        ] {
+            assert!(s.parse::<ProfileId>().is_err(), "{s:?} should not parse");
+        }
+    }
+
+    #[test]
+    fn test_profile_id_from_str_prop() {
+        arbtest::builder().run(|u| {
+            let id: ProfileId = u.arbitrary()?;
+            assert_eq!(Ok(id), id.to_string().parse());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_profile_id_hash() {
+        let ids: std::collections::HashSet<ProfileId> = [3176, 4635035, 3176]
+            .into_iter()
+            .map(ProfileId::from)
+            .collect();
+        assert_eq!(2, ids.len());
+        assert!(ids.contains(&ProfileId::from(4635035)));
+    }
+
+    #[test]
+    fn test_game_modes_best() {
+        let profile: Profile =
+            serde_json::from_str(include_str!("../../testdata/profile/neptune.json"))
+                .expect("should deserialize");
+        let modes = profile.modes.unwrap();
+
+        let names: Vec<_> = modes.iter().map(|(name, _)| name).collect();
+        assert!(names.contains(&"rm_solo"));
+        let (name, stats) = modes.best().expect("should have a best mode");
+        let max = modes
+            .iter()
+            .filter(|(name, _)| *name != "rm_1v1")
+            .filter_map(|(_, stats)| stats.rating)
+            .max();
+        assert_eq!(max, stats.rating);
+        assert_ne!("rm_1v1", name);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_game_modes_best_skips_rm_1v1() {
+        let stats = |rating| GameModeStats {
+            rating: Some(rating),
+            ..serde_json::from_str("{}").unwrap()
+        };
+        let mut modes: GameModes = serde_json::from_str("{}").unwrap();
+        assert_eq!(None, modes.best());
+
+        modes.rm_1v1 = Some(stats(2000));
+        modes.qm_1v1 = Some(stats(1200));
+        modes.custom = Some(stats(1200));
+        assert_eq!(3, modes.iter().count());
+        let (name, best) = modes.best().expect("should have a best mode");
+        assert_eq!("qm_1v1", name);
+        assert_eq!(Some(1200), best.rating);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_game_modes_totals() {
+        let stats = |games, wins| GameModeStats {
+            games_count: Some(games),
+            wins_count: Some(wins),
+            ..serde_json::from_str("{}").unwrap()
+        };
+        let mut modes: GameModes = serde_json::from_str("{}").unwrap();
+        assert_eq!(0, modes.total_games());
+        assert_eq!(None, modes.overall_win_rate());
+
+        modes.rm_solo = Some(stats(30, 20));
+        modes.rm_1v1 = Some(stats(30, 20));
+        modes.qm_2v2 = Some(stats(10, 0));
+        modes.custom = Some(GameModeStats {
+            wins_count: None,
+            ..stats(10, 0)
+        });
+        assert_eq!(50, modes.total_games());
+        assert_eq!(20, modes.total_wins());
+        assert_eq!(Some(40.0), modes.overall_win_rate());
+    }
+
+    #[test]
+    fn test_game_modes_get() {
+        let profile: Profile =
+            serde_json::from_str(include_str!("../../testdata/profile/jigly.json"))
+                .expect("should deserialize");
+        let modes = profile.modes.unwrap();
+        for (name, stats) in modes.iter() {
+            let leaderboard = name.trim_end_matches("_elo").parse::<Leaderboard>();
+            match leaderboard {
+                Ok(leaderboard) if name != "rm_1v1_elo" => {
+                    assert_eq!(Some(stats), modes.get(leaderboard), "{name}")
+                }
+                _ => {}
+            }
+        }
+        assert!(modes.get(Leaderboard::RmSolo).is_some());
+        assert!(modes.get(Leaderboard::Rm2v2).is_some());
+        assert_eq!(None, modes.get(Leaderboard::QmFfa));
+        assert_eq!(None, modes.get(Leaderboard::RmSoloConsole));
+    }
+
+    #[test]
+    fn test_site_url() {
+        assert_eq!(
+            "https://aoe4world.com/players/3176",
+            ProfileId::from(3176).site_url()
+        );
+        for json in [
+            include_str!("../../testdata/profile/housedhorse.json"),
+            include_str!("../../testdata/profile/jigly.json"),
+            include_str!("../../testdata/profile/neptune.json"),
+        ] {
+            let mut profile: Profile = serde_json::from_str(json).expect("should deserialize");
+            let field = profile
+                .site_url
+                .take()
+                .expect("fixture should have site_url");
+            // The API links over plain HTTP.
+            assert_eq!(field.replace("http://", "https://"), profile.site_url());
+        }
+    }
+
+    #[test]
+    fn test_profile_mode() {
+        use strum::VariantArray;
+
+        let mut profile: Profile =
+            serde_json::from_str(include_str!("../../testdata/profile/jigly.json"))
+                .expect("should deserialize");
+        let modes = profile.modes.clone().unwrap();
+        for &leaderboard in Leaderboard::VARIANTS {
+            assert_eq!(
+                modes.get(leaderboard),
+                profile.mode(leaderboard),
+                "{leaderboard}"
+            );
+        }
+        assert!(profile.mode(Leaderboard::RmSolo).is_some());
+
+        profile.modes = None;
+        assert_eq!(None, profile.mode(Leaderboard::RmSolo));
+    }
+
+    #[test]
+    fn test_rating_history_sorted() {
+        let entry = |rating| RatingHistoryEntry {
+            rating: Some(rating),
+            ..serde_json::from_str("{}").unwrap()
+        };
+        let stats = GameModeStats {
+            rating_history: [("10", 1010), ("9", 1009), ("100", 1100), ("foo", 0)]
+                .into_iter()
+                .map(|(key, rating)| (key.to_string(), entry(rating)))
+                .collect(),
+            ..serde_json::from_str("{}").unwrap()
+        };
+        let sorted: Vec<_> = stats
+            .rating_history_sorted()
+            .into_iter()
+            .map(|(key, entry)| (key, entry.rating))
+            .collect();
+        assert_eq!(
+            vec![(9, Some(1009)), (10, Some(1010)), (100, Some(1100))],
+            sorted
+        );
+
+        let profile: Profile =
+            serde_json::from_str(include_str!("../../testdata/profile/neptune.json"))
+                .expect("should deserialize");
+        let stats = profile.modes.unwrap().rm_solo.unwrap();
+        let sorted = stats.rating_history_sorted();
+        assert_eq!(stats.rating_history.len(), sorted.len());
+        assert!(sorted.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_rating_history_timestamps() {
+        let profile: Profile =
+            serde_json::from_str(include_str!("../../testdata/profile/neptune.json"))
+                .expect("should deserialize");
+        let stats = profile.modes.unwrap().rm_solo.unwrap();
+        for (key, entry) in &stats.rating_history {
+            let timestamp = entry.timestamp.expect("entry should have a timestamp");
+            assert_eq!(key, &timestamp.timestamp().to_string());
+        }
+        let (_, last) = *stats.rating_history_sorted().last().unwrap();
+        assert_eq!(stats.last_game_at, last.timestamp);
+
+        let stats: GameModeStats = serde_json::from_value(serde_json::json!({
+            "rating_history": {"1668117990": {}, "foo": {}},
+        }))
+        .unwrap();
+        assert_eq!(
+            Some("2022-11-10T22:06:30Z".parse().unwrap()),
+            stats.rating_history["1668117990"].timestamp
+        );
+        assert_eq!(None, stats.rating_history["foo"].timestamp);
+    }
+
+    #[test]
+    fn test_civ_game_length_durations() {
+        let profile: Profile =
+            serde_json::from_str(include_str!("../../testdata/profile/neptune.json"))
+                .expect("should deserialize");
+        let civs = &profile.modes.unwrap().rm_solo.unwrap().civilizations;
+
+        let game_length = civs[0].game_length.as_ref().unwrap();
+        assert_eq!(
+            Some(chrono::Duration::milliseconds(1_389_392)),
+            game_length.average_duration()
+        );
+        assert_eq!(
+            Some(chrono::Duration::milliseconds(1_404_500)),
+            game_length.median_duration()
+        );
+        assert_eq!(
+            Some(chrono::Duration::seconds(1425)),
+            game_length.wins_median_duration()
+        );
+        assert_eq!(
+            Some(chrono::Duration::seconds(1290)),
+            game_length.losses_median_duration()
+        );
+
+        let game_length = civs[2].game_length.as_ref().unwrap();
+        assert_eq!(None, game_length.average_duration());
+    }
+
+    #[test]
+    fn test_seconds_to_duration() {
+        assert_eq!(Some(chrono::Duration::zero()), seconds_to_duration(0.0));
+        assert_eq!(None, seconds_to_duration(-1.0));
+        assert_eq!(None, seconds_to_duration(f64::NAN));
+        assert_eq!(None, seconds_to_duration(f64::INFINITY));
+    }
 }
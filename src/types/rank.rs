@@ -3,6 +3,31 @@
 //! Types related to a player's rank league.
 
 use serde::{Deserialize, Serialize};
+use strum::VariantArray;
+
+use super::leaderboards::Leaderboard;
+
+/// Minimum ranked points for each league, from highest to lowest. Anything below the last
+/// threshold is [`League::Bronze1`].
+const RANKED_POINTS_THRESHOLDS: &[(i64, League)] = &[
+    (1600, League::Conqueror3),
+    (1500, League::Conqueror2),
+    (1400, League::Conqueror1),
+    (1300, League::Diamond3),
+    (1200, League::Diamond2),
+    (1100, League::Diamond1),
+    (1000, League::Platinum3),
+    (900, League::Platinum2),
+    (800, League::Platinum1),
+    (750, League::Gold3),
+    (700, League::Gold2),
+    (650, League::Gold1),
+    (600, League::Silver3),
+    (550, League::Silver2),
+    (500, League::Silver1),
+    (450, League::Bronze3),
+    (400, League::Bronze2),
+];
 
 /// A player's rank league and division (e.g. Conq III).
 #[derive(
@@ -85,7 +110,129 @@ pub enum League {
     Conqueror4,
 }
 
+/// A league without its division (e.g. Conqueror).
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::VariantArray,
+    strum::EnumString,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+#[cfg_attr(test, derive(arbitrary::Arbitrary))]
+pub enum LeagueTier {
+    /// No rank.
+    Unranked,
+    Bronze,
+    Silver,
+    Gold,
+    Platinum,
+    Diamond,
+    Conqueror,
+}
+
 impl League {
+    /// Returns the league's tier, without its division.
+    pub fn tier(&self) -> LeagueTier {
+        match self {
+            League::Unranked => LeagueTier::Unranked,
+            League::Bronze1 | League::Bronze2 | League::Bronze3 => LeagueTier::Bronze,
+            League::Silver1 | League::Silver2 | League::Silver3 => LeagueTier::Silver,
+            League::Gold1 | League::Gold2 | League::Gold3 => LeagueTier::Gold,
+            League::Platinum1 | League::Platinum2 | League::Platinum3 => LeagueTier::Platinum,
+            League::Diamond1 | League::Diamond2 | League::Diamond3 => LeagueTier::Diamond,
+            League::Conqueror1 | League::Conqueror2 | League::Conqueror3 | League::Conqueror4 => {
+                LeagueTier::Conqueror
+            }
+        }
+    }
+
+    /// Returns the league's division within its tier, from 1 to 4, or `None` if unranked.
+    pub fn division(&self) -> Option<u8> {
+        match self {
+            League::Unranked => None,
+            League::Bronze1
+            | League::Silver1
+            | League::Gold1
+            | League::Platinum1
+            | League::Diamond1
+            | League::Conqueror1 => Some(1),
+            League::Bronze2
+            | League::Silver2
+            | League::Gold2
+            | League::Platinum2
+            | League::Diamond2
+            | League::Conqueror2 => Some(2),
+            League::Bronze3
+            | League::Silver3
+            | League::Gold3
+            | League::Platinum3
+            | League::Diamond3
+            | League::Conqueror3 => Some(3),
+            League::Conqueror4 => Some(4),
+        }
+    }
+
+    /// Returns the league corresponding to a rating on a leaderboard.
+    ///
+    /// Leagues are only awarded on the ranked leaderboards ([`Leaderboard::RmSolo`],
+    /// [`Leaderboard::RmTeam`] and their console equivalents), where the rating is ranked
+    /// points. Ratings on every other leaderboard are ELO, which has no leagues, so they
+    /// map to [`League::Unranked`]. [`League::Conqueror4`] is reserved for professional
+    /// players and can't be derived from a rating.
+    pub fn from_rating(rating: i64, leaderboard: Leaderboard) -> League {
+        let ranked = matches!(
+            leaderboard,
+            Leaderboard::RmSolo
+                | Leaderboard::RmTeam
+                | Leaderboard::RmSoloConsole
+                | Leaderboard::RmTeamConsole
+        );
+        if !ranked {
+            return League::Unranked;
+        }
+        RANKED_POINTS_THRESHOLDS
+            .iter()
+            .find(|(min, _)| rating >= *min)
+            .map_or(League::Bronze1, |(_, league)| *league)
+    }
+
+    /// Returns the league one rung up the ladder, or `None` for the top league.
+    ///
+    /// [`League::Unranked`] is not part of the ladder, so it has no next or previous league.
+    pub fn next(&self) -> Option<League> {
+        let i = self.ladder_index()?;
+        League::VARIANTS.get(i + 1).copied()
+    }
+
+    /// Returns the league one rung down the ladder, or `None` for the bottom league.
+    ///
+    /// [`League::Unranked`] is not part of the ladder, so it has no next or previous league.
+    pub fn previous(&self) -> Option<League> {
+        let i = self.ladder_index()?;
+        League::VARIANTS
+            .get(i.checked_sub(1)?)
+            .copied()
+            .filter(|league| !league.is_unranked())
+    }
+
+    /// Index of the league in [`League::VARIANTS`], or `None` if it is not on the ladder.
+    fn ladder_index(&self) -> Option<usize> {
+        if self.is_unranked() {
+            return None;
+        }
+        League::VARIANTS.iter().position(|league| league == self)
+    }
+
     /// Is the league unranked?
     pub fn is_unranked(&self) -> bool {
         matches!(self, League::Unranked)
@@ -155,5 +302,112 @@ mod test_super {
 
     test_serde_roundtrip_prop!(League);
 
+    test_serde_roundtrip_prop!(LeagueTier);
+
     test_enum_to_string!(League);
+    test_enum_to_string!(LeagueTier);
+
+    #[test]
+    fn test_tier_division() {
+        assert_eq!(LeagueTier::Unranked, League::Unranked.tier());
+        assert_eq!(None, League::Unranked.division());
+        assert_eq!(LeagueTier::Conqueror, League::Conqueror3.tier());
+        assert_eq!(Some(3), League::Conqueror3.division());
+        assert_eq!(Some(4), League::Conqueror4.division());
+
+        for league in League::VARIANTS {
+            let tier = league.tier();
+            assert!(
+                league.to_string().starts_with(&tier.to_string()),
+                "{league} should be in {tier}"
+            );
+            if let Some(division) = league.division() {
+                assert!(league.to_string().ends_with(&division.to_string()));
+            }
+            assert_eq!(league.is_bronze(), tier == LeagueTier::Bronze);
+            assert_eq!(league.is_conqueror(), tier == LeagueTier::Conqueror);
+        }
+    }
+
+    #[test]
+    fn test_from_rating() {
+        let cases = [
+            (i64::MIN, League::Bronze1),
+            (0, League::Bronze1),
+            (399, League::Bronze1),
+            (400, League::Bronze2),
+            (449, League::Bronze2),
+            (450, League::Bronze3),
+            (499, League::Bronze3),
+            (500, League::Silver1),
+            (549, League::Silver1),
+            (550, League::Silver2),
+            (599, League::Silver2),
+            (600, League::Silver3),
+            (649, League::Silver3),
+            (650, League::Gold1),
+            (699, League::Gold1),
+            (700, League::Gold2),
+            (749, League::Gold2),
+            (750, League::Gold3),
+            (799, League::Gold3),
+            (800, League::Platinum1),
+            (899, League::Platinum1),
+            (900, League::Platinum2),
+            (999, League::Platinum2),
+            (1000, League::Platinum3),
+            (1099, League::Platinum3),
+            (1100, League::Diamond1),
+            (1199, League::Diamond1),
+            (1200, League::Diamond2),
+            (1299, League::Diamond2),
+            (1300, League::Diamond3),
+            (1399, League::Diamond3),
+            (1400, League::Conqueror1),
+            (1499, League::Conqueror1),
+            (1500, League::Conqueror2),
+            (1599, League::Conqueror2),
+            (1600, League::Conqueror3),
+            (i64::MAX, League::Conqueror3),
+        ];
+        for (rating, league) in cases {
+            for leaderboard in [Leaderboard::RmSolo, Leaderboard::RmTeamConsole] {
+                assert_eq!(
+                    league,
+                    League::from_rating(rating, leaderboard),
+                    "{rating} on {leaderboard}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_next_previous() {
+        assert_eq!(Some(League::Bronze2), League::Bronze1.next());
+        assert_eq!(None, League::Bronze1.previous());
+        assert_eq!(Some(League::Silver1), League::Bronze3.next());
+        assert_eq!(Some(League::Bronze3), League::Silver1.previous());
+        assert_eq!(Some(League::Conqueror4), League::Conqueror3.next());
+        assert_eq!(None, League::Conqueror4.next());
+        assert_eq!(None, League::Unranked.next());
+        assert_eq!(None, League::Unranked.previous());
+
+        let mut ladder = vec![League::Bronze1];
+        while let Some(next) = ladder.last().unwrap().next() {
+            ladder.push(next);
+        }
+        assert_eq!(League::VARIANTS[1..], ladder);
+        for pair in ladder.windows(2) {
+            assert!(pair[0] < pair[1]);
+            assert_eq!(Some(pair[0]), pair[1].previous());
+        }
+    }
+
+    #[test]
+    fn test_from_rating_elo() {
+        assert_eq!(
+            League::Unranked,
+            League::from_rating(1600, Leaderboard::Qm1v1)
+        );
+    }
 }
@@ -0,0 +1,13 @@
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+//! Checks that queries missing a required parameter cannot be sent.
+//!
+//! The expected errors change between compiler releases, so this only runs with
+//! `--ignored`, on the toolchain pinned in the unit tests workflow.
+
+#[test]
+#[ignore = "compiler output is only stable on the pinned toolchain"]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,5 @@
+use prelate_rs::query::LeaderboardQuery;
+
+fn main() {
+    let _ = LeaderboardQuery::default().with_rank_range(1..=10).get(10);
+}
@@ -0,0 +1,5 @@
+use prelate_rs::query::ProfileGamesQuery;
+
+fn main() {
+    let _ = ProfileGamesQuery::default().with_include_alts(true).get(10);
+}
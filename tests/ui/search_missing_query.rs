@@ -0,0 +1,5 @@
+use prelate_rs::query::SearchQuery;
+
+fn main() {
+    let _ = SearchQuery::default().with_exact(true).get(10);
+}